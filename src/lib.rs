@@ -2,13 +2,68 @@
 
 use core::panic;
 
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env, Vec};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, token, Address, BytesN,
+    Env, Map, String, Symbol, Vec,
+};
+
+const DAY_IN_LEDGERS: u32 = 17280;
+const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+const STORAGE_VERSION: u32 = 2;
+const MAX_DESCRIPTION_LEN: u32 = 64;
+const MAX_TTL_EXTENSION: u32 = 30 * DAY_IN_LEDGERS;
+const MAX_FEE_BPS: u32 = 1_000;
+
+/// Interface of a registry contract that maintains a list of members.
+#[contractclient(name = "RegistryClient")]
+pub trait Registry {
+    fn members(env: Env) -> Vec<Address>;
+}
+
+/// Interface of a contract that issues claim receipts.
+#[contractclient(name = "AttestationClient")]
+pub trait Attestation {
+    fn mint(env: Env, to: Address);
+}
 
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
     Init,
     Balance,
+    Depositor,
+    Token,
+    Pool,
+    Schedule,
+    ClaimLog(u32),
+    ClaimLogLen,
+    BeneficiaryCount,
+    ClaimedAmounts,
+    AutoRefund,
+    Admin,
+    UpgradePending,
+    Version,
+    Description,
+    Attestation,
+    MaxAmount,
+    Stream,
+    DepositTime,
+    MinClaimInterval,
+    LastClaimLedger,
+    Operator(Address),
+    ClaimDelay,
+    PendingClaim(Address),
+    Blacklist,
+    ProtocolFeeBps,
+    Treasury,
+    CreditClaims,
+    Credits,
+    Renounced,
+    RenouncedTotal,
+    AdminApproval(Address),
+    Irrevocable,
+    Weighted,
 }
 
 #[derive(Clone)]
@@ -16,6 +71,8 @@ pub enum DataKey {
 pub enum TimeBoundKind {
     Before,
     After,
+    /// Claimable at any time; the timestamp is ignored.
+    None,
 }
 
 #[derive(Clone)]
@@ -35,27 +92,989 @@ pub struct ClaimableBalance {
     pub beneficiaries: Vec<Address>,
     pub claimed_beneficiaries: Vec<Address>,
     pub time_bound: TimeBound,
+    /// When true, anyone may trigger a claim on behalf of a listed beneficiary.
+    pub public_claim: bool,
+    /// When true, a failed claim transfer marks the beneficiary as pending instead of reverting.
+    pub defer_failed_transfers: bool,
+    /// Beneficiaries whose claim transfer failed and who may retry regardless of the time bound.
+    pub pending_beneficiaries: Vec<Address>,
+    /// When true, a beneficiary may only claim once every earlier beneficiary has claimed, is
+    /// pending or is blacklisted.
+    pub ordered: bool,
+    /// When false, claims are blocked until the depositor calls `set_released(true)`.
+    pub released: bool,
+    /// When true, every claim also requires the depositor's authorization.
+    pub co_sign: bool,
+}
+
+#[derive(Clone)]
+#[contracttype]
+/// The parameters of a [`ClaimableBalance`] deposit, bundled for
+/// [`MultiPartyClaimableBalanceContract::deposit_config`].
+pub struct DepositConfig {
+    pub token: Address,
+    pub amount_per_beneficiary: i128,
+    pub beneficiaries: Vec<Address>,
+    pub timebound: TimeBound,
+    pub public_claim: bool,
+    pub defer_failed_transfers: bool,
+    pub ordered: bool,
+    /// When true, claims wait for `set_released(true)`.
+    pub requires_release: bool,
+    /// Optional cap on the total deposited.
+    pub max_total: Option<i128>,
+    pub co_sign: bool,
+    /// When true, the final claim refunds any excess to the depositor.
+    pub auto_refund: bool,
+    /// A human-readable description of at most 64 bytes, or empty for none.
+    pub description: String,
+    /// The highest protocol fee, in basis points, the depositor accepts.
+    pub max_fee_bps: Option<u32>,
+    /// When true, the administrator can never halt the distribution with `revoke_all`.
+    pub irrevocable: bool,
+}
+
+#[derive(Clone)]
+#[contracttype]
+/// Represents a shared pool that listed beneficiaries draw from, first come, first served.
+pub struct PooledBalance {
+    pub token: Address,
+    /// The amount still available in the pool.
+    pub total_pool: i128,
+    pub max_per_beneficiary: i128,
+    pub beneficiaries: Vec<Address>,
+    /// The part of `max_per_beneficiary` each beneficiary who has claimed may still draw.
+    pub remaining_caps: Map<Address, i128>,
+    pub time_bound: TimeBound,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Tranche {
+    pub unlock_timestamp: u64,
+    /// The share of each beneficiary's allocation released by this tranche, in basis points.
+    pub fraction_bps: u32,
+    /// The last timestamp at which this tranche may be claimed; afterwards it is forfeited.
+    pub claim_deadline: Option<u64>,
+}
+
+#[derive(Clone)]
+#[contracttype]
+/// Represents a balance released to each beneficiary in scheduled tranches.
+pub struct ScheduledBalance {
+    pub token: Address,
+    pub amount_per_beneficiary: i128,
+    pub total_amount: i128,
+    pub beneficiaries: Vec<Address>,
+    pub tranches: Vec<Tranche>,
+    /// The number of leading tranches each beneficiary has already claimed or forfeited.
+    pub claimed_tranches: Map<Address, u32>,
+    /// The total of tranche amounts forfeited after their claim deadline.
+    pub forfeited: i128,
+}
+
+#[derive(Clone)]
+#[contracttype]
+/// Represents a balance streamed to each beneficiary at a constant rate per second.
+pub struct StreamedBalance {
+    pub token: Address,
+    pub amount_per_beneficiary: i128,
+    pub total_amount: i128,
+    pub beneficiaries: Vec<Address>,
+    pub rate_per_second: i128,
+    /// The timestamp from which each beneficiary's stream accrues.
+    pub start: u64,
+    /// The timestamp each beneficiary last claimed at.
+    pub last_claim: Map<Address, u64>,
+    /// The amount each beneficiary has claimed so far.
+    pub claimed: Map<Address, i128>,
+}
+
+#[derive(Clone)]
+#[contracttype]
+/// Represents a total split among beneficiaries by basis-point weights.
+pub struct WeightedBalance {
+    pub token: Address,
+    pub total_amount: i128,
+    pub beneficiaries: Vec<Address>,
+    /// Each beneficiary's share, in the order of `beneficiaries`.
+    pub shares: Vec<i128>,
+    pub claimed_beneficiaries: Vec<Address>,
+    pub time_bound: TimeBound,
+}
+
+#[derive(Clone)]
+#[contracttype]
+/// The original layout of [`ClaimableBalance`], kept so old instances can be migrated.
+pub struct ClaimableBalanceV1 {
+    pub token: Address,
+    pub amount_per_beneficiary: i128,
+    pub total_amount: i128,
+    pub beneficiaries: Vec<Address>,
+    pub claimed_beneficiaries: Vec<Address>,
+    pub time_bound: TimeBound,
 }
 
 #[contract]
 pub struct MultiPartyClaimableBalanceContract;
 
+/// Both bounds are inclusive: a claim exactly at `time_bound.timestamp` satisfies either kind.
 fn check_time_bound(env: &Env, time_bound: &TimeBound) -> bool {
     let ledger_timestamp = env.ledger().timestamp();
 
     match time_bound.kind {
         TimeBoundKind::Before => ledger_timestamp <= time_bound.timestamp,
         TimeBoundKind::After => ledger_timestamp >= time_bound.timestamp,
+        TimeBoundKind::None => true,
+    }
+}
+
+fn require_time_bound(env: &Env, time_bound: &TimeBound) {
+    if !check_time_bound(env, time_bound) {
+        match time_bound.kind {
+            TimeBoundKind::Before => panic!("claim window closed"),
+            TimeBoundKind::After => panic!("claim not yet open"),
+            TimeBoundKind::None => {}
+        }
+    }
+}
+
+fn is_expired(env: &Env, time_bound: &TimeBound) -> bool {
+    matches!(time_bound.kind, TimeBoundKind::Before)
+        && time_bound.timestamp <= env.ledger().timestamp()
+}
+
+fn check_not_expired(env: &Env, time_bound: &TimeBound) {
+    if is_expired(env, time_bound) {
+        panic!("timebound already expired");
     }
 }
+
 fn is_initialized(env: &Env) -> bool {
     env.storage().instance().has(&DataKey::Init)
 }
 
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .unwrap_or_else(|| panic!("admin not set"));
+    admin.require_auth();
+    admin
+}
+
+fn is_upgrade_pending(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::UpgradePending)
+        .unwrap_or(false)
+}
+
+fn check_no_upgrade(env: &Env) {
+    if is_upgrade_pending(env) {
+        panic!("upgrade pending");
+    }
+}
+
+/// Whether `amount` is above the administrator's configured maximum, a guard against
+/// fat-finger deposits rather than a protocol limit.
+fn exceeds_max_amount(env: &Env, amount: i128) -> bool {
+    env.storage()
+        .instance()
+        .get::<_, i128>(&DataKey::MaxAmount)
+        .is_some_and(|max_amount| amount > max_amount)
+}
+
+fn is_blacklisted(env: &Env, address: &Address) -> bool {
+    env.storage()
+        .instance()
+        .get::<_, Vec<Address>>(&DataKey::Blacklist)
+        .is_some_and(|blacklist| blacklist.contains(address))
+}
+
+/// Rejects addresses on the administrator's blacklist.
+fn check_not_blacklisted(env: &Env, address: &Address) {
+    if is_blacklisted(env, address) {
+        panic!("beneficiary blacklisted");
+    }
+}
+
+fn renounced(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Renounced)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn has_duplicates(beneficiaries: &Vec<Address>) -> bool {
+    beneficiaries
+        .iter()
+        .enumerate()
+        .any(|(index, beneficiary)| {
+            beneficiaries.first_index_of(&beneficiary) != Some(index as u32)
+        })
+}
+
+/// Returns the message a new distribution paying `amount_per_beneficiary` to each of
+/// `beneficiaries` is rejected with, or `None` if it passes the checks shared by every
+/// deposit path. The contract must not be initialized yet when the deposit `initializes`
+/// it. [`MultiPartyClaimableBalanceContract::validate_deposit`] runs the same
+/// checks without panicking.
+fn deposit_error(
+    env: &Env,
+    amount_per_beneficiary: i128,
+    beneficiaries: &Vec<Address>,
+    initializes: bool,
+) -> Option<&'static str> {
+    if amount_per_beneficiary < 0 {
+        Some("amount must be positive")
+    } else if beneficiaries.len() > 10 {
+        Some("too many beneficiaries")
+    } else if has_duplicates(beneficiaries) {
+        Some("duplicate beneficiary")
+    } else if exceeds_max_amount(env, amount_per_beneficiary) {
+        Some("exceeds max amount")
+    } else if beneficiaries
+        .iter()
+        .any(|beneficiary| is_blacklisted(env, &beneficiary))
+    {
+        Some("beneficiary blacklisted")
+    } else if amount_per_beneficiary
+        .checked_mul(beneficiaries.len() as i128)
+        .is_none()
+    {
+        Some("amount overflow")
+    } else if is_upgrade_pending(env) {
+        Some("upgrade pending")
+    } else if initializes && is_initialized(env) {
+        Some("contract has been already initialized")
+    } else {
+        None
+    }
+}
+
+fn check_deposit(
+    env: &Env,
+    amount_per_beneficiary: i128,
+    beneficiaries: &Vec<Address>,
+    initializes: bool,
+) {
+    if let Some(message) = deposit_error(env, amount_per_beneficiary, beneficiaries, initializes) {
+        panic!("{}", message);
+    }
+}
+
+/// Enforces the configured minimum number of ledgers between a beneficiary's claims and
+/// records the current ledger as their latest claim.
+fn check_claim_interval(env: &Env, beneficiary: &Address) {
+    let Some(interval) = env
+        .storage()
+        .instance()
+        .get::<_, u32>(&DataKey::MinClaimInterval)
+    else {
+        return;
+    };
+    let mut last_claim_ledger: Map<Address, u32> = env
+        .storage()
+        .instance()
+        .get(&DataKey::LastClaimLedger)
+        .unwrap_or_else(|| Map::new(env));
+    let sequence = env.ledger().sequence();
+    if let Some(last) = last_claim_ledger.get(beneficiary.clone()) {
+        if sequence < last.saturating_add(interval) {
+            panic!("claim too frequent");
+        }
+    }
+    last_claim_ledger.set(beneficiary.clone(), sequence);
+    env.storage()
+        .instance()
+        .set(&DataKey::LastClaimLedger, &last_claim_ledger);
+}
+
+fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+fn log_claim(env: &Env, beneficiary: &Address, amount: i128) {
+    let index: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::ClaimLogLen)
+        .unwrap_or(0);
+    let key = DataKey::ClaimLog(index);
+    env.storage()
+        .persistent()
+        .set(&key, &(beneficiary.clone(), env.ledger().timestamp()));
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&DataKey::ClaimLogLen, &(index + 1));
+
+    let mut claimed_amounts: Map<Address, i128> = env
+        .storage()
+        .instance()
+        .get(&DataKey::ClaimedAmounts)
+        .unwrap_or_else(|| Map::new(env));
+    let claimed = claimed_amounts.get(beneficiary.clone()).unwrap_or(0);
+    claimed_amounts.set(beneficiary.clone(), claimed + amount);
+    env.storage()
+        .instance()
+        .set(&DataKey::ClaimedAmounts, &claimed_amounts);
+}
+
+/// Transfers the tokens held above the outstanding obligation and `keep` to the depositor.
+fn transfer_excess(env: &Env, keep: i128) -> i128 {
+    let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+    let token: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+    let token_client = token::Client::new(env, &token);
+    let excess = token_client.balance(&env.current_contract_address()) - obligation(env) - keep;
+    if excess > 0 {
+        token_client.transfer(&env.current_contract_address(), &depositor, &excess);
+        env.events()
+            .publish((symbol_short!("sweep"), depositor), excess);
+    }
+    excess
+}
+
+/// Stores the balance, or removes it once every beneficiary has claimed, refunding any
+/// excess to the depositor when auto-refund is enabled.
+///
+/// On the final claim the token balance is checked against the accounting before any
+/// refund: holding less than the outstanding obligation is an accounting bug and panics.
+/// Any surplus goes back to the depositor, except that without auto-refund the unswept
+/// renounced shares stay for [`sweep`].
+///
+/// [`sweep`]: MultiPartyClaimableBalanceContract::sweep
+fn save_balance(env: &Env, claimable_balance: &ClaimableBalance) {
+    if claimable_balance.claimed_beneficiaries.len() == claimable_balance.beneficiaries.len() {
+        env.storage().instance().remove(&DataKey::Balance);
+        let renounced_total: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RenouncedTotal)
+            .unwrap_or(0);
+        env.storage().instance().remove(&DataKey::Renounced);
+        env.storage().instance().remove(&DataKey::RenouncedTotal);
+        let auto_refund = env
+            .storage()
+            .instance()
+            .get(&DataKey::AutoRefund)
+            .unwrap_or(false);
+        let residual = token::Client::new(env, &claimable_balance.token)
+            .balance(&env.current_contract_address())
+            - obligation(env);
+        if residual < 0 {
+            panic!("residual balance");
+        }
+        transfer_excess(env, if auto_refund { 0 } else { renounced_total });
+    } else {
+        env.storage()
+            .instance()
+            .set(&DataKey::Balance, claimable_balance);
+    }
+}
+
+fn unclaimed_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get::<_, ClaimableBalance>(&DataKey::Balance)
+        .map_or(0, |claimable_balance| {
+            claimable_balance.beneficiaries.len() - claimable_balance.claimed_beneficiaries.len()
+        })
+}
+
+/// With a claim delay configured, requires that `beneficiary` initiated the claim and that
+/// the delay has elapsed.
+/// The checks a first claim must pass; `initiate_claim` runs them too, so a claim is only
+/// initiated when it could go through.
+fn check_claimable(env: &Env, claimable_balance: &ClaimableBalance, beneficiary: &Address) {
+    require_time_bound(env, &claimable_balance.time_bound);
+    if !claimable_balance.released {
+        panic!("not released");
+    }
+    if claimable_balance
+        .claimed_beneficiaries
+        .contains(beneficiary)
+    {
+        panic!("beneficiary already claimed");
+    }
+}
+
+/// Drops any initiated claims left behind when a balance ends without paying everyone, so
+/// they cannot shortcut the claim delay of a later deposit.
+fn clear_pending_claims(env: &Env, beneficiaries: &Vec<Address>) {
+    for beneficiary in beneficiaries.iter() {
+        env.storage()
+            .instance()
+            .remove(&DataKey::PendingClaim(beneficiary));
+    }
+}
+
+fn check_claim_delay(env: &Env, beneficiary: &Address) {
+    if !env.storage().instance().has(&DataKey::ClaimDelay) {
+        return;
+    }
+    let not_before: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::PendingClaim(beneficiary.clone()))
+        .unwrap_or_else(|| panic!("claim not initiated"));
+    if env.ledger().timestamp() < not_before {
+        panic!("claim delay not elapsed");
+    }
+}
+
+/// Panics unless every beneficiary listed before `beneficiary` has been cleared: claimed,
+/// pending or blacklisted, the same ones `distribute_range` passes over, so a self-claim is
+/// never blocked where a batch would go through.
+fn check_claim_order(env: &Env, claimable_balance: &ClaimableBalance, beneficiary: &Address) {
+    if !claimable_balance.ordered {
+        return;
+    }
+    for earlier in claimable_balance.beneficiaries.iter() {
+        if earlier == *beneficiary {
+            break;
+        }
+        let cleared = claimable_balance.claimed_beneficiaries.contains(&earlier)
+            || claimable_balance.pending_beneficiaries.contains(&earlier)
+            || is_blacklisted(env, &earlier);
+        if !cleared {
+            panic!("out of order claim");
+        }
+    }
+}
+
+fn check_token(env: &Env, token: &Address) {
+    let locked_token: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+    if *token != locked_token {
+        panic!("token mismatch");
+    }
+}
+
+/// Loads the active balance. `DataKey::Init` outlives the balance, so a contract whose
+/// balance was removed after the final claim is told apart from one never funded.
+fn load_balance(env: &Env) -> ClaimableBalance {
+    if !is_initialized(env) {
+        panic!("not initialized");
+    }
+    let claimable_balance: ClaimableBalance = env
+        .storage()
+        .instance()
+        .get(&DataKey::Balance)
+        .unwrap_or_else(|| panic!("distribution complete"));
+    check_token(env, &claimable_balance.token);
+    claimable_balance
+}
+
+fn load_pool(env: &Env) -> PooledBalance {
+    let pool: PooledBalance = env.storage().instance().get(&DataKey::Pool).unwrap();
+    check_token(env, &pool.token);
+    pool
+}
+
+fn obligation(env: &Env) -> i128 {
+    let credited: i128 = credits(env).values().iter().sum();
+    credited + distribution_obligation(env)
+}
+
+fn distribution_obligation(env: &Env) -> i128 {
+    if let Some(balance) = env
+        .storage()
+        .instance()
+        .get::<_, ClaimableBalance>(&DataKey::Balance)
+    {
+        return balance.total_amount;
+    }
+    if let Some(pool) = env
+        .storage()
+        .instance()
+        .get::<_, PooledBalance>(&DataKey::Pool)
+    {
+        return pool.total_pool;
+    }
+    if let Some(schedule) = env
+        .storage()
+        .instance()
+        .get::<_, ScheduledBalance>(&DataKey::Schedule)
+    {
+        return schedule.total_amount - expired_tranches(env, &schedule);
+    }
+    if let Some(weighted) = env
+        .storage()
+        .instance()
+        .get::<_, WeightedBalance>(&DataKey::Weighted)
+    {
+        return weighted.total_amount;
+    }
+    env.storage()
+        .instance()
+        .get::<_, StreamedBalance>(&DataKey::Stream)
+        .map_or(0, |stream| stream.total_amount)
+}
+
+fn check_schedule(tranches: &Vec<Tranche>) {
+    if tranches.is_empty() || tranches.len() > 10 {
+        panic!("invalid schedule");
+    }
+    let mut total_bps = 0;
+    let mut previous_unlock = None;
+    for tranche in tranches.iter() {
+        if tranche.fraction_bps == 0
+            || previous_unlock >= Some(tranche.unlock_timestamp)
+            || tranche
+                .claim_deadline
+                .is_some_and(|deadline| deadline < tranche.unlock_timestamp)
+        {
+            panic!("invalid schedule");
+        }
+        total_bps += tranche.fraction_bps;
+        previous_unlock = Some(tranche.unlock_timestamp);
+    }
+    if total_bps != 10_000 {
+        panic!("invalid schedule");
+    }
+}
+
+/// Returns `bps` basis points of `amount`, rounded down. `amount` is split so neither
+/// product can overflow, even for amounts near `i128::MAX`.
+fn bps_of(amount: i128, bps: u32) -> i128 {
+    let bps = bps as i128;
+    amount / 10_000 * bps + amount % 10_000 * bps / 10_000
+}
+
+/// Splits `total` by `weights_bps` with the largest-remainder method: every share is rounded
+/// down, then the units left over go one each to the largest remainders, the earlier
+/// beneficiary first on ties, so the shares always sum to `total`.
+fn weighted_shares(env: &Env, total: i128, weights_bps: &Vec<u32>) -> Vec<i128> {
+    let mut shares = Vec::new(env);
+    let mut remainders = Vec::new(env);
+    let mut left = total;
+    for weight in weights_bps.iter() {
+        let share = bps_of(total, weight);
+        shares.push_back(share);
+        remainders.push_back(total % 10_000 * weight as i128 % 10_000);
+        left -= share;
+    }
+    while left > 0 {
+        let mut largest = 0;
+        for index in 1..remainders.len() {
+            if remainders.get(index).unwrap() > remainders.get(largest).unwrap() {
+                largest = index;
+            }
+        }
+        shares.set(largest, shares.get(largest).unwrap() + 1);
+        remainders.set(largest, -1);
+        left -= 1;
+    }
+    shares
+}
+
+/// Returns the part of `amount` released by the tranche at `index` alone.
+fn tranche_amount(amount: i128, tranches: &Vec<Tranche>, index: u32) -> i128 {
+    released_by_tranches(amount, tranches, index + 1)
+        - released_by_tranches(amount, tranches, index)
+}
+
+/// Returns the total of tranches past their claim deadline that beneficiaries have not
+/// claimed yet. They are forfeited whether or not the beneficiary ever claims again.
+fn expired_tranches(env: &Env, schedule: &ScheduledBalance) -> i128 {
+    let mut expired = 0;
+    for beneficiary in schedule.beneficiaries.iter() {
+        let claimed = schedule.claimed_tranches.get(beneficiary).unwrap_or(0);
+        for index in claimed..schedule.tranches.len() {
+            match schedule.tranches.get(index).unwrap().claim_deadline {
+                Some(deadline) if deadline < env.ledger().timestamp() => {
+                    expired +=
+                        tranche_amount(schedule.amount_per_beneficiary, &schedule.tranches, index)
+                }
+                _ => {}
+            }
+        }
+    }
+    expired
+}
+
+/// Returns the part of `amount` released by the first `count` tranches. The remainder of
+/// the basis-point division is released with the final tranche.
+fn released_by_tranches(amount: i128, tranches: &Vec<Tranche>, count: u32) -> i128 {
+    let mut released_bps = 0;
+    for tranche in tranches.slice(0..count).iter() {
+        released_bps += tranche.fraction_bps;
+    }
+    bps_of(amount, released_bps)
+}
+
+/// Returns a [`DepositConfig`] with every optional claim mode and setting disabled.
+fn default_config(
+    env: &Env,
+    token: Address,
+    amount_per_beneficiary: i128,
+    beneficiaries: Vec<Address>,
+    timebound: TimeBound,
+) -> DepositConfig {
+    DepositConfig {
+        token,
+        amount_per_beneficiary,
+        beneficiaries,
+        timebound,
+        public_claim: false,
+        defer_failed_transfers: false,
+        ordered: false,
+        requires_release: false,
+        max_total: None,
+        co_sign: false,
+        auto_refund: false,
+        description: String::from_str(env, ""),
+        max_fee_bps: None,
+        irrevocable: false,
+    }
+}
+
+fn credits(env: &Env) -> Map<Address, i128> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Credits)
+        .unwrap_or(Map::new(env))
+}
+
+fn credit_claims(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::CreditClaims)
+        .unwrap_or(false)
+}
+
+fn add_credit(env: &Env, to: &Address, amount: i128) {
+    let mut credits = credits(env);
+    credits.set(to.clone(), credits.get(to.clone()).unwrap_or(0) + amount);
+    env.storage().instance().set(&DataKey::Credits, &credits);
+}
+
+/// Pays `amount` to `to`, or adds it to their credit for a later [`withdraw`] when claims
+/// are credited.
+///
+/// [`withdraw`]: MultiPartyClaimableBalanceContract::withdraw
+fn pay_out(env: &Env, token: &Address, to: &Address, amount: i128) {
+    if credit_claims(env) {
+        add_credit(env, to, amount);
+    } else {
+        token::Client::new(env, token).transfer(&env.current_contract_address(), to, &amount);
+    }
+}
+
+/// Pays `amount` to `to` as [`pay_out`]. With `defer`, a failed transfer is credited to `to`
+/// instead, emitting a `pending` event, so one failing recipient cannot block the others.
+fn pay_out_or_credit(env: &Env, token: &Address, to: &Address, amount: i128, defer: bool) {
+    if !defer || credit_claims(env) {
+        pay_out(env, token, to, amount);
+    } else if token::Client::new(env, token)
+        .try_transfer(&env.current_contract_address(), to, &amount)
+        .is_err()
+    {
+        add_credit(env, to, amount);
+        env.events()
+            .publish((symbol_short!("pending"), to.clone()), amount);
+    }
+}
+
+/// Returns the protocol fee on `amount` at the current rate, rejecting the deposit when the
+/// rate is above the depositor's `max_fee_bps`.
+fn deposit_fee(env: &Env, amount: i128, max_fee_bps: Option<u32>) -> i128 {
+    let fee_bps: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::ProtocolFeeBps)
+        .unwrap_or(0);
+    if max_fee_bps.is_some_and(|max_fee_bps| fee_bps > max_fee_bps) {
+        panic!("fee above limit");
+    }
+    bps_of(amount, fee_bps)
+}
+
+/// Transfers `total` from `from` into the contract, through the contract's allowance when
+/// `use_allowance` is set, and forwards the protocol `fee` out of it to the treasury with a
+/// `fee` event.
+fn collect_deposit(
+    env: &Env,
+    from: &Address,
+    token: &Address,
+    total: i128,
+    fee: i128,
+    use_allowance: bool,
+) {
+    let contract = env.current_contract_address();
+    let token_client = token::Client::new(env, token);
+    if use_allowance {
+        token_client.transfer_from(&contract, from, &contract, &total);
+    } else {
+        token_client.transfer(from, &contract, &total);
+    }
+    if fee > 0 {
+        let treasury: Address = env.storage().instance().get(&DataKey::Treasury).unwrap();
+        token_client.transfer(&contract, &treasury, &fee);
+        env.events().publish((symbol_short!("fee"), treasury), fee);
+    }
+}
+
+/// Rejects a deposit by `from` into a contract whose administrator `from` has not approved
+/// with [`MultiPartyClaimableBalanceContract::approve_admin`].
+fn check_admin_approved(env: &Env, from: &Address) {
+    if let Some(admin) = env.storage().instance().get::<_, Address>(&DataKey::Admin) {
+        let approved: Option<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminApproval(from.clone()));
+        if approved != Some(admin) {
+            panic!("admin not approved");
+        }
+    }
+}
+
+/// Collects a new distribution's deposit as [`collect_deposit`] and records the state every
+/// distribution shares, marking the contract initialized. The caller stores the
+/// distribution itself.
+fn start_distribution(
+    env: &Env,
+    from: &Address,
+    token: &Address,
+    beneficiary_count: u32,
+    total: i128,
+    fee: i128,
+    use_allowance: bool,
+) {
+    check_admin_approved(env, from);
+    collect_deposit(env, from, token, total, fee, use_allowance);
+    env.storage().instance().set(&DataKey::Depositor, from);
+    env.storage().instance().set(&DataKey::Token, token);
+    env.storage()
+        .instance()
+        .set(&DataKey::BeneficiaryCount, &beneficiary_count);
+    env.storage().instance().set(&DataKey::Init, &true);
+    env.storage()
+        .instance()
+        .set(&DataKey::DepositTime, &env.ledger().timestamp());
+    env.storage()
+        .instance()
+        .set(&DataKey::Version, &STORAGE_VERSION);
+}
+
+/// Validates and stores a new balance and collects its deposit from `from`, through the
+/// contract's allowance when `use_allowance` is set.
+fn create_balance(env: &Env, from: &Address, config: DepositConfig, use_allowance: bool) {
+    check_deposit(
+        env,
+        config.amount_per_beneficiary,
+        &config.beneficiaries,
+        true,
+    );
+
+    check_not_expired(env, &config.timebound);
+
+    let total_amount = config.amount_per_beneficiary * config.beneficiaries.len() as i128;
+    if let Some(max_total) = config.max_total {
+        if total_amount > max_total {
+            panic!("exceeds max total");
+        }
+    }
+    let fee_per_beneficiary = deposit_fee(env, config.amount_per_beneficiary, config.max_fee_bps);
+    let amount_per_beneficiary = config.amount_per_beneficiary - fee_per_beneficiary;
+    let fee = fee_per_beneficiary * config.beneficiaries.len() as i128;
+    if config.description.len() > MAX_DESCRIPTION_LEN {
+        panic!("description too long");
+    }
+    if config.description.len() > 0 {
+        env.storage()
+            .instance()
+            .set(&DataKey::Description, &config.description);
+    }
+    if config.auto_refund {
+        env.storage().instance().set(&DataKey::AutoRefund, &true);
+    }
+    if config.irrevocable {
+        env.storage().instance().set(&DataKey::Irrevocable, &true);
+    }
+
+    env.events().publish(
+        (symbol_short!("deposit"), from.clone()),
+        (
+            config.token.clone(),
+            amount_per_beneficiary,
+            config.beneficiaries.clone(),
+        ),
+    );
+    start_distribution(
+        env,
+        from,
+        &config.token,
+        config.beneficiaries.len(),
+        total_amount,
+        fee,
+        use_allowance,
+    );
+    env.storage().instance().set(
+        &DataKey::Balance,
+        &ClaimableBalance {
+            token: config.token,
+            amount_per_beneficiary,
+            total_amount: total_amount - fee,
+            beneficiaries: config.beneficiaries,
+            claimed_beneficiaries: Vec::new(env),
+            time_bound: config.timebound,
+            public_claim: config.public_claim,
+            defer_failed_transfers: config.defer_failed_transfers,
+            pending_beneficiaries: Vec::new(env),
+            ordered: config.ordered,
+            released: !config.requires_release,
+            co_sign: config.co_sign,
+        },
+    );
+}
+
+/// Asks the attestation contract, if one is set, to mint a claim receipt for `beneficiary`.
+/// The call is best-effort: a failing attestation contract does not revert the claim.
+fn attest_claim(env: &Env, beneficiary: &Address) {
+    if let Some(attestation) = env
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::Attestation)
+    {
+        let _ = AttestationClient::new(env, &attestation).try_mint(beneficiary);
+    }
+}
+
+/// Pays `beneficiary`'s share, to `split` destinations by basis points if given. A split
+/// always requires the beneficiary's authorization; with deferred transfers, a part whose
+/// transfer fails is credited to its destination instead of leaving the claim pending.
+/// When `operator` is given, its authorization replaces the beneficiary's.
+fn process_claim(
+    env: &Env,
+    beneficiary: &Address,
+    split: Option<(Vec<Address>, Vec<u32>)>,
+    operator: Option<&Address>,
+) -> Option<(i128, i128)> {
+    check_no_upgrade(env);
+    extend_instance_ttl(env);
+    let mut claimable_balance = load_balance(env);
+    match operator {
+        Some(operator) => operator.require_auth(),
+        None if !claimable_balance.public_claim || split.is_some() => beneficiary.require_auth(),
+        None => {}
+    }
+    if claimable_balance.co_sign {
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+    }
+
+    if !claimable_balance.beneficiaries.contains(beneficiary) {
+        panic!("beneficiary not in list");
+    }
+    check_not_blacklisted(env, beneficiary);
+    let pending_index = claimable_balance
+        .pending_beneficiaries
+        .first_index_of(beneficiary);
+    if pending_index.is_none() {
+        check_claimable(env, &claimable_balance, beneficiary);
+        check_claim_order(env, &claimable_balance, beneficiary);
+        check_claim_delay(env, beneficiary);
+    }
+
+    let contract = env.current_contract_address();
+    let self_transfer = match &split {
+        Some((destinations, _)) => destinations.contains(&contract),
+        None => *beneficiary == contract,
+    };
+    if self_transfer {
+        panic!("invalid self-transfer");
+    }
+
+    // Every outstanding share and credit must be covered, so this payout never spends
+    // tokens owed to someone else.
+    let token_client = token::Client::new(env, &claimable_balance.token);
+    if claimable_balance.total_amount < claimable_balance.amount_per_beneficiary
+        || token_client.balance(&contract) < obligation(env)
+    {
+        panic!("underfunded distribution");
+    }
+    if let Some((destinations, bps)) = split {
+        let amount = claimable_balance.amount_per_beneficiary;
+        let mut paid = 0;
+        for (index, destination) in destinations.iter().enumerate() {
+            let part = if index as u32 == destinations.len() - 1 {
+                amount - paid
+            } else {
+                bps_of(amount, bps.get(index as u32).unwrap())
+            };
+            pay_out_or_credit(
+                env,
+                &claimable_balance.token,
+                &destination,
+                part,
+                claimable_balance.defer_failed_transfers,
+            );
+            paid += part;
+        }
+    } else if claimable_balance.defer_failed_transfers && !credit_claims(env) {
+        let transferred = token_client
+            .try_transfer(
+                &env.current_contract_address(),
+                beneficiary,
+                &claimable_balance.amount_per_beneficiary,
+            )
+            .is_ok();
+        if !transferred {
+            if pending_index.is_none() {
+                claimable_balance
+                    .pending_beneficiaries
+                    .push_back(beneficiary.clone());
+                env.storage()
+                    .instance()
+                    .set(&DataKey::Balance, &claimable_balance);
+            }
+            env.events().publish(
+                (symbol_short!("pending"), beneficiary.clone()),
+                claimable_balance.amount_per_beneficiary,
+            );
+            return None;
+        }
+    } else {
+        pay_out(
+            env,
+            &claimable_balance.token,
+            beneficiary,
+            claimable_balance.amount_per_beneficiary,
+        );
+    }
+
+    if let Some(index) = pending_index {
+        claimable_balance.pending_beneficiaries.remove(index);
+    }
+    claimable_balance
+        .claimed_beneficiaries
+        .push_back(beneficiary.clone());
+    claimable_balance.total_amount -= claimable_balance.amount_per_beneficiary;
+    log_claim(env, beneficiary, claimable_balance.amount_per_beneficiary);
+    env.storage()
+        .instance()
+        .remove(&DataKey::PendingClaim(beneficiary.clone()));
+
+    save_balance(env, &claimable_balance);
+    attest_claim(env, beneficiary);
+
+    Some((
+        claimable_balance.amount_per_beneficiary,
+        claimable_balance.total_amount,
+    ))
+}
+
 #[contractimpl]
 /// Implementation of a multi-party claimable balance contract.
 impl MultiPartyClaimableBalanceContract {
-    /// Deposits funds into the contract, initializing it with the specified parameters.
+    /// Deposits funds into the contract, initializing it and emitting a `deposit` event.
     ///
     /// # Arguments
     ///
@@ -66,11 +1085,15 @@ impl MultiPartyClaimableBalanceContract {
     /// * `beneficiaries` - The list of beneficiary addresses.
     /// * `timebound` - The timebound for claiming the funds.
     ///
+    /// Optional claim modes are disabled; use [`Self::deposit_config`] to enable them.
+    ///
     /// # Panics
     ///
     /// This function will panic under the following conditions:
-    /// * If `amount_per_beneficiary` is less than 0.
+    /// * If `amount_per_beneficiary` is less than 0 or above the configured maximum.
     /// * If the number of `beneficiaries` exceeds 10.
+    /// * If the total overflows.
+    /// * If a `Before` time bound is not in the future.
     /// * If the contract has already been initialized.
     pub fn deposit(
         env: &Env,
@@ -80,99 +1103,2620 @@ impl MultiPartyClaimableBalanceContract {
         beneficiaries: Vec<Address>,
         timebound: TimeBound,
     ) {
-        if amount_per_beneficiary < 0 {
-            panic!("amount must be positive");
-        }
-
-        if beneficiaries.len() > 10 {
-            panic!("too many beneficiaries");
-        }
-
-        if is_initialized(&env) {
-            panic!("contract has been already initialized");
-        }
-
-        from.require_auth();
-
-        let total_amount = &amount_per_beneficiary * beneficiaries.len() as i128;
-        let empty_claimed: Vec<Address> = Vec::new(&env);
-        token::Client::new(&env, &token).transfer(
-            &from,
-            &env.current_contract_address(),
-            &total_amount,
-        );
-        env.storage().instance().set(
-            &DataKey::Balance,
-            &ClaimableBalance {
-                token,
-                amount_per_beneficiary,
-                total_amount,
-                beneficiaries,
-                claimed_beneficiaries: empty_claimed,
-                time_bound: timebound,
-            },
+        Self::deposit_config(
+            env,
+            from,
+            default_config(env, token, amount_per_beneficiary, beneficiaries, timebound),
         );
-        env.storage().instance().set(&DataKey::Init, &true);
     }
 
-    /// Claims funds from the contract for a specific beneficiary.
+    /// Returns whether [`Self::deposit`] would accept these parameters, running the
+    /// deposit-time validations without transferring or storing anything. Frontends can
+    /// call it before asking for a wallet signature; it does not check the depositor's
+    /// token balance.
     ///
     /// # Arguments
     ///
     /// * `env` - The contract environment.
-    /// * `beneficiary` - The address of the beneficiary claiming the funds.
-    ///
-    /// # Panics
-    ///
-    /// This function will panic under the following conditions:
-    /// * If `beneficiary` is not in the list of beneficiaries.
+    /// * `amount_per_beneficiary` - The amount of tokens each beneficiary would receive.
+    /// * `beneficiaries` - The list of beneficiary addresses.
+    /// * `timebound` - The timebound for claiming the funds.
+    pub fn validate_deposit(
+        env: &Env,
+        amount_per_beneficiary: i128,
+        beneficiaries: Vec<Address>,
+        timebound: TimeBound,
+    ) -> bool {
+        deposit_error(env, amount_per_beneficiary, &beneficiaries, true).is_none()
+            && !is_expired(env, &timebound)
+    }
+
+    /// Deposits a total pot split evenly among `beneficiaries`. Each beneficiary receives
+    /// `total / beneficiaries.len()`; the remainder is change that is never taken from `from`.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `from` - The address from which the funds are being deposited.
+    /// * `token` - The address of the token being deposited.
+    /// * `total` - The pot to divide among the beneficiaries.
+    /// * `beneficiaries` - The list of beneficiary addresses.
+    /// * `timebound` - The timebound for claiming the funds.
+    ///
+    /// # Returns
+    ///
+    /// The change left with `from`, `total % beneficiaries.len()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `beneficiaries` is empty, or under the same conditions as [`Self::deposit`].
+    pub fn deposit_pot(
+        env: &Env,
+        from: Address,
+        token: Address,
+        total: i128,
+        beneficiaries: Vec<Address>,
+        timebound: TimeBound,
+    ) -> i128 {
+        if beneficiaries.is_empty() {
+            panic!("no beneficiaries");
+        }
+        let count = beneficiaries.len() as i128;
+        Self::deposit_config(
+            env,
+            from,
+            default_config(env, token, total / count, beneficiaries, timebound),
+        );
+        total % count
+    }
+
+    /// Deposits funds like [`Self::deposit`], taking every parameter from a
+    /// [`DepositConfig`], including settings that otherwise need separate calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `from` - The address from which the funds are being deposited.
+    /// * `config` - The deposit parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Self::deposit`], if the description is longer
+    /// than 64 bytes, or if the protocol fee is above `max_fee_bps`.
+    pub fn deposit_config(env: &Env, from: Address, config: DepositConfig) {
+        from.require_auth();
+        create_balance(env, &from, config, false);
+    }
+
+    /// Deposits funds like [`Self::deposit`], pulling them with `transfer_from` against an
+    /// allowance `from` has granted to this contract.
+    ///
+    /// # Arguments
+    ///
+    /// Takes the same arguments as [`Self::deposit`]. `from` must have approved the contract
+    /// as spender for at least `amount_per_beneficiary * beneficiaries.len()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Self::deposit`], or if the allowance is
+    /// insufficient.
+    pub fn deposit_from(
+        env: &Env,
+        from: Address,
+        token: Address,
+        amount_per_beneficiary: i128,
+        beneficiaries: Vec<Address>,
+        timebound: TimeBound,
+    ) {
+        from.require_auth();
+        create_balance(
+            env,
+            &from,
+            default_config(env, token, amount_per_beneficiary, beneficiaries, timebound),
+            true,
+        );
+    }
+
+    /// Deposits funds like [`Self::deposit`], taking the beneficiaries from the `members` of
+    /// a registry contract.
+    ///
+    /// The member list is copied at deposit time, so later registry changes do not affect the
+    /// distribution. All optional claim modes are disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `from` - The address from which the funds are being deposited.
+    /// * `token` - The address of the token being deposited.
+    /// * `amount_per_beneficiary` - The amount of tokens each member will receive.
+    /// * `registry` - The address of the registry contract.
+    /// * `timebound` - The timebound for claiming the funds.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Self::deposit`].
+    pub fn deposit_from_registry(
+        env: &Env,
+        from: Address,
+        token: Address,
+        amount_per_beneficiary: i128,
+        registry: Address,
+        timebound: TimeBound,
+    ) {
+        from.require_auth();
+        let beneficiaries = RegistryClient::new(env, &registry).members();
+        create_balance(
+            env,
+            &from,
+            default_config(env, token, amount_per_beneficiary, beneficiaries, timebound),
+            false,
+        );
+    }
+
+    /// Splits tokens among `beneficiaries` in one call: the total is transferred in and
+    /// immediately paid out, without storing a balance or initializing the contract.
+    ///
+    /// Emits a `split` event with `(token, amount_per_beneficiary, beneficiaries)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `from` - The address from which the funds are being deposited.
+    /// * `token` - The address of the token being distributed.
+    /// * `amount_per_beneficiary` - The amount of tokens each beneficiary will receive.
+    /// * `beneficiaries` - The list of beneficiary addresses.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `amount_per_beneficiary` is less than 0 or above the configured maximum.
+    /// * If the number of `beneficiaries` exceeds 10.
+    /// * If `beneficiaries` contains duplicates or blacklisted addresses.
+    /// * If the total amount overflows.
+    /// * If an upgrade is pending.
+    pub fn deposit_and_distribute(
+        env: &Env,
+        from: Address,
+        token: Address,
+        amount_per_beneficiary: i128,
+        beneficiaries: Vec<Address>,
+    ) {
+        check_deposit(env, amount_per_beneficiary, &beneficiaries, false);
+
+        from.require_auth();
+
+        let total_amount = amount_per_beneficiary * beneficiaries.len() as i128;
+        let fee_per_beneficiary = deposit_fee(env, amount_per_beneficiary, None);
+        let amount_per_beneficiary = amount_per_beneficiary - fee_per_beneficiary;
+        collect_deposit(
+            env,
+            &from,
+            &token,
+            total_amount,
+            fee_per_beneficiary * beneficiaries.len() as i128,
+            false,
+        );
+        let token_client = token::Client::new(env, &token);
+        for beneficiary in beneficiaries.iter() {
+            token_client.transfer(
+                &env.current_contract_address(),
+                &beneficiary,
+                &amount_per_beneficiary,
+            );
+        }
+        env.events().publish(
+            (symbol_short!("split"), from),
+            (token, amount_per_beneficiary, beneficiaries),
+        );
+    }
+
+    /// Claims funds from the contract for a specific beneficiary, extending the contract's
+    /// TTL when it is close to expiring.
+    ///
+    /// Emits a `claim` event whose data is `(amount, remaining_total)`, where
+    /// `remaining_total` is the amount still owed to unclaimed beneficiaries.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The beneficiary claiming the funds. Must authorize the call unless
+    ///   the balance was deposited with `public_claim`.
+    ///
+    /// # Returns
+    ///
+    /// The number of beneficiaries still unclaimed, 0 once the balance is removed.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `beneficiary` is not in the list of beneficiaries.
     /// * If the time bound for claiming the funds is not satisfied.
     /// * If the beneficiary has already claimed their share of the funds.
-    pub fn claim(env: &Env, beneficiary: Address) {
-        beneficiary.require_auth();
-        let mut claimable_balance: ClaimableBalance =
-            env.storage().instance().get(&DataKey::Balance).unwrap();
-        
-      
-        if !claimable_balance.beneficiaries.contains(&beneficiary) {
-            panic!("beneficiary not in list");
+    /// * If the contract holds less than one share.
+    ///
+    /// With `defer_failed_transfers` set, a failed transfer instead marks the beneficiary
+    /// pending and emits a `pending` event; pending beneficiaries may retry regardless of
+    /// the time bound.
+    pub fn claim(env: &Env, beneficiary: Address) -> u32 {
+        if let Some((amount, remaining_total)) = process_claim(env, &beneficiary, None, None) {
+            env.events().publish(
+                (symbol_short!("claim"), beneficiary),
+                (amount, remaining_total),
+            );
+        }
+        unclaimed_count(env)
+    }
+
+    /// Claims funds like [`Self::claim`], appending a memo to the claim event data.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary claiming the funds.
+    /// * `memo` - A tag included in the claim event data for off-chain reconciliation.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Self::claim`].
+    pub fn claim_with_memo(env: &Env, beneficiary: Address, memo: Symbol) {
+        if let Some((amount, remaining_total)) = process_claim(env, &beneficiary, None, None) {
+            env.events().publish(
+                (symbol_short!("claim"), beneficiary),
+                (amount, remaining_total, memo),
+            );
+        }
+    }
+
+    /// Claims funds like [`Self::claim`], splitting the beneficiary's share across several
+    /// `destinations` by basis points. The rounding remainder goes to the last destination.
+    ///
+    /// Each part is paid like a claim, so it is credited when claims are credited. With
+    /// deferred transfers, a part whose transfer fails is credited to its destination, which
+    /// can [`Self::withdraw`] it later, and a `pending` event is emitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary claiming the funds. Must authorize
+    ///   the call, even if the balance allows public claims.
+    /// * `destinations` - The addresses receiving parts of the share.
+    /// * `bps` - The part of the share for each destination, in basis points.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `destinations` and `bps` differ in length or are empty.
+    /// * If `bps` does not sum to 10000.
+    /// * Under the same conditions as [`Self::claim`].
+    pub fn claim_split(env: &Env, beneficiary: Address, destinations: Vec<Address>, bps: Vec<u32>) {
+        if destinations.len() != bps.len() || destinations.is_empty() {
+            panic!("length mismatch");
         }
-        if !check_time_bound(&env, &claimable_balance.time_bound) {
-            panic!("time bound not satisfied");
+        if bps.iter().sum::<u32>() != 10000 {
+            panic!("invalid split");
         }
-        if claimable_balance
-            .claimed_beneficiaries
-            .contains(&beneficiary)
+
+        if let Some((amount, remaining_total)) =
+            process_claim(env, &beneficiary, Some((destinations, bps)), None)
         {
-            panic!("beneficiary already claimed");
+            env.events().publish(
+                (symbol_short!("claim"), beneficiary),
+                (amount, remaining_total),
+            );
         }
-        claimable_balance
-            .claimed_beneficiaries
-            .push_back(beneficiary.clone());
-        token::Client::new(&env, &claimable_balance.token).transfer(
-            &env.current_contract_address(),
-            &beneficiary,
-            &claimable_balance.amount_per_beneficiary,
+    }
+
+    /// Authorizes `operator` to claim on behalf of `beneficiary` until the `until`
+    /// timestamp, inclusive. Replaces any earlier authorization; an `until` in the past
+    /// revokes it.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The beneficiary delegating their claim.
+    /// * `operator` - The address allowed to claim for them.
+    /// * `until` - The last timestamp at which the operator may claim.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `beneficiary` is not in the active balance's list of
+    /// beneficiaries.
+    pub fn authorize_operator(env: &Env, beneficiary: Address, operator: Address, until: u64) {
+        beneficiary.require_auth();
+        if !load_balance(env).beneficiaries.contains(&beneficiary) {
+            panic!("beneficiary not in list");
+        }
+        let key = DataKey::Operator(beneficiary.clone());
+        env.storage()
+            .persistent()
+            .set(&key, &(operator.clone(), until));
+        env.storage().persistent().extend_ttl(
+            &key,
+            INSTANCE_LIFETIME_THRESHOLD,
+            INSTANCE_BUMP_AMOUNT,
         );
+        env.events()
+            .publish((symbol_short!("operator"), beneficiary), (operator, until));
+    }
+
+    /// Claims funds like [`Self::claim`] on behalf of `beneficiary`, authorized by an
+    /// operator set with [`Self::authorize_operator`]. The funds go to the beneficiary.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `operator` - The operator making the claim. Must authorize the call.
+    /// * `beneficiary` - The beneficiary being claimed for.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `operator` is not the beneficiary's authorized operator.
+    /// * If the operator's authorization has expired.
+    /// * Under the same conditions as [`Self::claim`].
+    pub fn claim_for(env: &Env, operator: Address, beneficiary: Address) -> u32 {
+        let (authorized, until): (Address, u64) = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Operator(beneficiary.clone()))
+            .unwrap_or_else(|| panic!("operator not authorized"));
+        if authorized != operator {
+            panic!("operator not authorized");
+        }
+        if env.ledger().timestamp() > until {
+            panic!("operator authorization expired");
+        }
 
-        if &claimable_balance.claimed_beneficiaries.len() == &claimable_balance.beneficiaries.len()
+        if let Some((amount, remaining_total)) =
+            process_claim(env, &beneficiary, None, Some(&operator))
         {
-            env.storage().instance().remove(&DataKey::Balance);
-        } else {
-            env.storage().instance().set(
-                &DataKey::Balance,
-                &ClaimableBalance {
-                    token: claimable_balance.token,
-                    amount_per_beneficiary: claimable_balance.amount_per_beneficiary,
-                    total_amount: claimable_balance.total_amount
-                        - claimable_balance.amount_per_beneficiary,
-                    beneficiaries: claimable_balance.beneficiaries,
-                    claimed_beneficiaries: claimable_balance.claimed_beneficiaries,
-                    time_bound: claimable_balance.time_bound,
-                },
+            env.events().publish(
+                (symbol_short!("claim"), beneficiary),
+                (amount, remaining_total),
             );
         }
-        
+        unclaimed_count(env)
+    }
+
+    /// Sets a delay, in seconds, that makes claims a two-step flow, or removes it with
+    /// `None`. With a delay, a beneficiary calls [`Self::initiate_claim`] and can claim only
+    /// once the delay has passed, giving the depositor a window to [`Self::cancel_claim`].
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `delay` - The seconds between initiating and finalizing a claim.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the contract has not been initialized.
+    pub fn set_claim_delay(env: &Env, delay: Option<u64>) {
+        if !is_initialized(env) {
+            panic!("not initialized");
+        }
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        match delay {
+            Some(delay) => env.storage().instance().set(&DataKey::ClaimDelay, &delay),
+            None => env.storage().instance().remove(&DataKey::ClaimDelay),
+        }
+        env.events()
+            .publish((symbol_short!("config"), symbol_short!("delay")), delay);
+    }
+
+    /// Records the intent to claim, starting the claim delay. Emits an `initiate` event
+    /// with the timestamp from which the claim can be finalized.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary initiating the claim.
+    ///
+    /// # Returns
+    ///
+    /// The timestamp from which [`Self::finalize_claim`] succeeds.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If no claim delay is configured.
+    /// * If `beneficiary` is not in the list of beneficiaries, or is blacklisted.
+    /// * If the time bound is not satisfied or the balance is not released.
+    /// * If the beneficiary has already claimed or initiated a claim.
+    pub fn initiate_claim(env: &Env, beneficiary: Address) -> u64 {
+        beneficiary.require_auth();
+        let delay: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ClaimDelay)
+            .unwrap_or_else(|| panic!("no claim delay"));
+        let claimable_balance = load_balance(env);
+        if !claimable_balance.beneficiaries.contains(&beneficiary) {
+            panic!("beneficiary not in list");
+        }
+        check_not_blacklisted(env, &beneficiary);
+        check_claimable(env, &claimable_balance, &beneficiary);
+        let key = DataKey::PendingClaim(beneficiary.clone());
+        if env.storage().instance().has(&key) {
+            panic!("claim already initiated");
+        }
+
+        let not_before = env.ledger().timestamp().saturating_add(delay);
+        env.storage().instance().set(&key, &not_before);
+        env.events()
+            .publish((symbol_short!("initiate"), beneficiary), not_before);
+        not_before
+    }
+
+    /// Completes a claim started with [`Self::initiate_claim`] once the delay has passed,
+    /// paying out like [`Self::claim`].
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary claiming the funds.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If the claim was not initiated or the delay has not elapsed.
+    /// * Under the same conditions as [`Self::claim`].
+    pub fn finalize_claim(env: &Env, beneficiary: Address) -> u32 {
+        Self::claim(env, beneficiary)
+    }
+
+    /// Cancels an initiated claim, for example when fraud monitoring flags it. The
+    /// beneficiary may initiate again later.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The beneficiary whose claim is cancelled.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the beneficiary has no initiated claim.
+    pub fn cancel_claim(env: &Env, beneficiary: Address) {
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        let key = DataKey::PendingClaim(beneficiary.clone());
+        if !env.storage().instance().has(&key) {
+            panic!("claim not initiated");
+        }
+        env.storage().instance().remove(&key);
+        env.events()
+            .publish((symbol_short!("cancel"), beneficiary), ());
+    }
+
+    /// Pushes payouts to a slice of the beneficiary list, so large distributions can be
+    /// paginated across transactions.
+    ///
+    /// Beneficiaries in `[start, start + count)` that have not claimed yet are paid and
+    /// marked as claimed, emitting a `claim` event each; those who already claimed, are
+    /// pending, or are blacklisted are skipped, so ranges are safe to interleave with
+    /// self-claims.
+    ///
+    /// A failed transfer does not stop the range: the beneficiary is marked pending, a
+    /// `pending` event is emitted, and they may retry with [`Self::claim`].
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `start` - The index of the first beneficiary to process.
+    /// * `count` - The maximum number of beneficiaries to process.
+    ///
+    /// # Returns
+    ///
+    /// The beneficiaries whose transfer failed in this call.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If the depositor has not authorized the call.
+    /// * If the time bound for claiming the funds is not satisfied.
+    pub fn distribute_range(env: &Env, start: u32, count: u32) -> Vec<Address> {
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        check_no_upgrade(env);
+        let mut claimable_balance = load_balance(env);
+        require_time_bound(env, &claimable_balance.time_bound);
+        if !claimable_balance.released {
+            panic!("not released");
+        }
+
+        let token_client = token::Client::new(env, &claimable_balance.token);
+        let end = start
+            .saturating_add(count)
+            .min(claimable_balance.beneficiaries.len());
+        let mut failed = Vec::new(env);
+        for index in start..end {
+            let beneficiary = claimable_balance.beneficiaries.get(index).unwrap();
+            if claimable_balance
+                .claimed_beneficiaries
+                .contains(&beneficiary)
+                || claimable_balance
+                    .pending_beneficiaries
+                    .contains(&beneficiary)
+                || is_blacklisted(env, &beneficiary)
+            {
+                continue;
+            }
+            check_claim_order(env, &claimable_balance, &beneficiary);
+            if token_client
+                .try_transfer(
+                    &env.current_contract_address(),
+                    &beneficiary,
+                    &claimable_balance.amount_per_beneficiary,
+                )
+                .is_err()
+            {
+                claimable_balance
+                    .pending_beneficiaries
+                    .push_back(beneficiary.clone());
+                env.events().publish(
+                    (symbol_short!("pending"), beneficiary.clone()),
+                    claimable_balance.amount_per_beneficiary,
+                );
+                failed.push_back(beneficiary);
+                continue;
+            }
+            claimable_balance
+                .claimed_beneficiaries
+                .push_back(beneficiary.clone());
+            claimable_balance.total_amount -= claimable_balance.amount_per_beneficiary;
+            log_claim(env, &beneficiary, claimable_balance.amount_per_beneficiary);
+            env.storage()
+                .instance()
+                .remove(&DataKey::PendingClaim(beneficiary.clone()));
+            env.events().publish(
+                (symbol_short!("claim"), beneficiary),
+                (
+                    claimable_balance.amount_per_beneficiary,
+                    claimable_balance.total_amount,
+                ),
+            );
+        }
+
+        save_balance(env, &claimable_balance);
+        failed
+    }
+
+    /// Deposits a shared pool that beneficiaries draw from, initializing the contract.
+    ///
+    /// Instead of fixed shares, each listed beneficiary may claim any amounts, in one or
+    /// several installments, up to a personal cap of `max_per_beneficiary`, until the pool
+    /// is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `from` - The address from which the funds are being deposited.
+    /// * `token` - The address of the token being deposited.
+    /// * `total_pool` - The total amount of tokens deposited into the pool.
+    /// * `max_per_beneficiary` - The maximum amount a single beneficiary may claim.
+    /// * `beneficiaries` - The list of beneficiary addresses.
+    /// * `timebound` - The timebound for claiming the funds.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If either amount is not positive, or is above the configured maximum per beneficiary.
+    /// * If the number of `beneficiaries` exceeds 10.
+    /// * If a `Before` time bound is not in the future.
+    /// * If the contract has already been initialized.
+    pub fn deposit_pool(
+        env: &Env,
+        from: Address,
+        token: Address,
+        total_pool: i128,
+        max_per_beneficiary: i128,
+        beneficiaries: Vec<Address>,
+        timebound: TimeBound,
+    ) {
+        if total_pool <= 0 || max_per_beneficiary <= 0 {
+            panic!("amount must be positive");
+        }
+
+        check_deposit(env, max_per_beneficiary, &beneficiaries, true);
+
+        if env
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::MaxAmount)
+            .is_some_and(|max_amount| {
+                max_amount
+                    .checked_mul(beneficiaries.len() as i128)
+                    .is_some_and(|max_total| total_pool > max_total)
+            })
+        {
+            panic!("exceeds max amount");
+        }
+
+        check_not_expired(env, &timebound);
+
+        from.require_auth();
+
+        let fee = deposit_fee(env, total_pool, None);
+        start_distribution(
+            env,
+            &from,
+            &token,
+            beneficiaries.len(),
+            total_pool,
+            fee,
+            false,
+        );
+        env.events().publish(
+            (symbol_short!("pool"), from),
+            (
+                token.clone(),
+                total_pool - fee,
+                max_per_beneficiary,
+                beneficiaries.clone(),
+            ),
+        );
+        env.storage().instance().set(
+            &DataKey::Pool,
+            &PooledBalance {
+                token,
+                total_pool: total_pool - fee,
+                max_per_beneficiary,
+                beneficiaries,
+                remaining_caps: Map::new(env),
+                time_bound: timebound,
+            },
+        );
+    }
+
+    /// Adds `amount` to the active pool from any address, crowd-funding the pot before
+    /// claims open. The protocol fee is deducted as on a deposit. Emits a `contrib` event
+    /// with the amount added and the new pool total.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `from` - The address contributing the funds.
+    /// * `amount` - The amount to add to the pool.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If there is no active pool.
+    /// * If `amount` is not positive.
+    /// * If the pool's time bound is not an `After` bound that is still in the future.
+    /// * If an upgrade is pending.
+    pub fn contribute(env: &Env, from: Address, amount: i128) {
+        if amount <= 0 {
+            panic!("amount must be positive");
+        }
+        check_no_upgrade(env);
+        let mut pool: PooledBalance = env
+            .storage()
+            .instance()
+            .get(&DataKey::Pool)
+            .unwrap_or_else(|| panic!("no active pool"));
+        check_token(env, &pool.token);
+        let open = match pool.time_bound.kind {
+            TimeBoundKind::After => check_time_bound(env, &pool.time_bound),
+            TimeBoundKind::Before | TimeBoundKind::None => true,
+        };
+        if open {
+            panic!("claim window open");
+        }
+
+        from.require_auth();
+
+        let fee = deposit_fee(env, amount, None);
+        collect_deposit(env, &from, &pool.token, amount, fee, false);
+        let amount = amount - fee;
+        pool.total_pool = pool
+            .total_pool
+            .checked_add(amount)
+            .unwrap_or_else(|| panic!("amount overflow"));
+        env.storage().instance().set(&DataKey::Pool, &pool);
+        env.events()
+            .publish((symbol_short!("contrib"), from), (amount, pool.total_pool));
+    }
+
+    /// Claims a specific amount from the pool for a beneficiary, reducing their remaining
+    /// personal cap.
+    ///
+    /// Emits a `claim` event whose data is `(amount, total_pool)`, where `total_pool` is
+    /// the amount left in the pool after this claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary claiming the funds.
+    /// * `amount` - The amount to claim.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `beneficiary` is not in the list of beneficiaries.
+    /// * If the time bound for claiming the funds is not satisfied.
+    /// * If `amount` is not positive or exceeds `max_per_beneficiary`.
+    /// * If `amount` exceeds the beneficiary's remaining personal cap.
+    /// * If `amount` exceeds what is left in the pool.
+    pub fn claim_amount(env: &Env, beneficiary: Address, amount: i128) {
+        beneficiary.require_auth();
+        check_no_upgrade(env);
+        extend_instance_ttl(env);
+        let mut pool = load_pool(env);
+
+        if !pool.beneficiaries.contains(&beneficiary) {
+            panic!("beneficiary not in list");
+        }
+        check_not_blacklisted(env, &beneficiary);
+        require_time_bound(env, &pool.time_bound);
+        if amount <= 0 {
+            panic!("amount must be positive");
+        }
+        if amount > pool.max_per_beneficiary {
+            panic!("exceeds max per beneficiary");
+        }
+        let remaining_cap = pool
+            .remaining_caps
+            .get(beneficiary.clone())
+            .unwrap_or(pool.max_per_beneficiary);
+        if amount > remaining_cap {
+            panic!("exceeds personal cap");
+        }
+        if amount > pool.total_pool {
+            panic!("insufficient pool");
+        }
+
+        pool.remaining_caps
+            .set(beneficiary.clone(), remaining_cap - amount);
+        pool.total_pool -= amount;
+        pay_out(env, &pool.token, &beneficiary, amount);
+
+        if pool.total_pool == 0 {
+            env.storage().instance().remove(&DataKey::Pool);
+        } else {
+            env.storage().instance().set(&DataKey::Pool, &pool);
+        }
+        log_claim(env, &beneficiary, amount);
+        env.events().publish(
+            (symbol_short!("claim"), beneficiary),
+            (amount, pool.total_pool),
+        );
+    }
+
+    /// Returns how much `beneficiary` may still claim from the pool under their personal cap.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If there is no active pool.
+    /// * If `beneficiary` is not in the list of beneficiaries.
+    pub fn remaining_cap(env: &Env, beneficiary: Address) -> i128 {
+        let pool: PooledBalance = env
+            .storage()
+            .instance()
+            .get(&DataKey::Pool)
+            .unwrap_or_else(|| panic!("no active pool"));
+        if !pool.beneficiaries.contains(&beneficiary) {
+            panic!("beneficiary not in list");
+        }
+        pool.remaining_caps
+            .get(beneficiary)
+            .unwrap_or(pool.max_per_beneficiary)
+    }
+
+    /// Deposits funds released to each beneficiary in a schedule of tranches, initializing
+    /// the contract.
+    ///
+    /// Each tranche unlocks `fraction_bps` of every beneficiary's allocation at
+    /// `unlock_timestamp`, claimable until its optional `claim_deadline`. Unlock timestamps
+    /// must strictly increase, no deadline may precede its unlock, and the fractions must
+    /// sum to 10000.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `from` - The address from which the funds are being deposited.
+    /// * `token` - The address of the token being deposited.
+    /// * `amount_per_beneficiary` - Each beneficiary's total allocation.
+    /// * `beneficiaries` - The list of beneficiary addresses.
+    /// * `tranches` - The release schedule, at most 10 tranches.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `amount_per_beneficiary` is less than 0 or above the configured maximum.
+    /// * If the number of `beneficiaries` exceeds 10.
+    /// * If the total amount overflows.
+    /// * If `tranches` is not a valid schedule.
+    /// * If the contract has already been initialized.
+    pub fn deposit_schedule(
+        env: &Env,
+        from: Address,
+        token: Address,
+        amount_per_beneficiary: i128,
+        beneficiaries: Vec<Address>,
+        tranches: Vec<Tranche>,
+    ) {
+        check_deposit(env, amount_per_beneficiary, &beneficiaries, true);
+
+        check_schedule(&tranches);
+
+        from.require_auth();
+
+        let total_amount = amount_per_beneficiary * beneficiaries.len() as i128;
+        let fee_per_beneficiary = deposit_fee(env, amount_per_beneficiary, None);
+        let amount_per_beneficiary = amount_per_beneficiary - fee_per_beneficiary;
+        let fee = fee_per_beneficiary * beneficiaries.len() as i128;
+        start_distribution(
+            env,
+            &from,
+            &token,
+            beneficiaries.len(),
+            total_amount,
+            fee,
+            false,
+        );
+        env.events().publish(
+            (symbol_short!("schedule"), from),
+            (token.clone(), amount_per_beneficiary, beneficiaries.clone()),
+        );
+        env.storage().instance().set(
+            &DataKey::Schedule,
+            &ScheduledBalance {
+                token,
+                amount_per_beneficiary,
+                total_amount: total_amount - fee,
+                beneficiaries,
+                tranches,
+                claimed_tranches: Map::new(env),
+                forfeited: 0,
+            },
+        );
+    }
+
+    /// Claims every unlocked tranche the beneficiary has not claimed yet.
+    ///
+    /// Tranches past their claim deadline are forfeited instead of paid: their amount is
+    /// added to `forfeited` and becomes excess the depositor can recover with [`Self::sweep`].
+    ///
+    /// Emits a `claim` event whose data is `(amount, remaining_total)`, unless every tranche
+    /// claimed was forfeited and nothing is paid.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary claiming the funds.
+    ///
+    /// # Returns
+    ///
+    /// The amount transferred to the beneficiary.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `beneficiary` is not in the list of beneficiaries.
+    /// * If the beneficiary claimed within the minimum claim interval.
+    /// * If no unclaimed tranche has unlocked.
+    pub fn claim_tranches(env: &Env, beneficiary: Address) -> i128 {
+        beneficiary.require_auth();
+        check_no_upgrade(env);
+        extend_instance_ttl(env);
+        let mut schedule: ScheduledBalance =
+            env.storage().instance().get(&DataKey::Schedule).unwrap();
+        check_token(env, &schedule.token);
+
+        if !schedule.beneficiaries.contains(&beneficiary) {
+            panic!("beneficiary not in list");
+        }
+        check_not_blacklisted(env, &beneficiary);
+        check_claim_interval(env, &beneficiary);
+        let claimed = schedule
+            .claimed_tranches
+            .get(beneficiary.clone())
+            .unwrap_or(0);
+        let mut unlocked = claimed;
+        while unlocked < schedule.tranches.len()
+            && schedule.tranches.get(unlocked).unwrap().unlock_timestamp <= env.ledger().timestamp()
+        {
+            unlocked += 1;
+        }
+        if unlocked == claimed {
+            panic!("nothing to claim");
+        }
+
+        let mut amount = 0;
+        let mut forfeited = 0;
+        for index in claimed..unlocked {
+            let part = tranche_amount(schedule.amount_per_beneficiary, &schedule.tranches, index);
+            match schedule.tranches.get(index).unwrap().claim_deadline {
+                Some(deadline) if deadline < env.ledger().timestamp() => forfeited += part,
+                _ => amount += part,
+            }
+        }
+        schedule.claimed_tranches.set(beneficiary.clone(), unlocked);
+        schedule.total_amount -= amount + forfeited;
+        schedule.forfeited += forfeited;
+        if amount > 0 {
+            pay_out(env, &schedule.token, &beneficiary, amount);
+        }
+
+        if schedule.total_amount == 0 {
+            env.storage().instance().remove(&DataKey::Schedule);
+        } else {
+            env.storage().instance().set(&DataKey::Schedule, &schedule);
+        }
+        if amount > 0 {
+            log_claim(env, &beneficiary, amount);
+            env.events().publish(
+                (symbol_short!("claim"), beneficiary),
+                (amount, schedule.total_amount),
+            );
+        }
+        amount
+    }
+
+    /// Deposits funds streamed continuously to each beneficiary, initializing the contract.
+    ///
+    /// From `start`, each beneficiary accrues `rate_per_second` until their
+    /// `amount_per_beneficiary` allocation is reached, and may claim the accrued amount at
+    /// any time.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `from` - The address from which the funds are being deposited.
+    /// * `token` - The address of the token being deposited.
+    /// * `amount_per_beneficiary` - Each beneficiary's total allocation.
+    /// * `beneficiaries` - The list of beneficiary addresses.
+    /// * `rate_per_second` - The amount each beneficiary accrues per second.
+    /// * `start` - The timestamp from which the streams accrue.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `amount_per_beneficiary` is less than 0 or above the configured maximum.
+    /// * If `rate_per_second` is not positive.
+    /// * If the number of `beneficiaries` exceeds 10.
+    /// * If the total amount overflows.
+    /// * If the contract has already been initialized.
+    pub fn deposit_stream(
+        env: &Env,
+        from: Address,
+        token: Address,
+        amount_per_beneficiary: i128,
+        beneficiaries: Vec<Address>,
+        rate_per_second: i128,
+        start: u64,
+    ) {
+        if rate_per_second <= 0 {
+            panic!("amount must be positive");
+        }
+
+        check_deposit(env, amount_per_beneficiary, &beneficiaries, true);
+
+        from.require_auth();
+
+        let total_amount = amount_per_beneficiary * beneficiaries.len() as i128;
+        let fee_per_beneficiary = deposit_fee(env, amount_per_beneficiary, None);
+        let amount_per_beneficiary = amount_per_beneficiary - fee_per_beneficiary;
+        let fee = fee_per_beneficiary * beneficiaries.len() as i128;
+        start_distribution(
+            env,
+            &from,
+            &token,
+            beneficiaries.len(),
+            total_amount,
+            fee,
+            false,
+        );
+        env.events().publish(
+            (symbol_short!("stream"), from),
+            (token.clone(), amount_per_beneficiary, beneficiaries.clone()),
+        );
+        env.storage().instance().set(
+            &DataKey::Stream,
+            &StreamedBalance {
+                token,
+                amount_per_beneficiary,
+                total_amount: total_amount - fee,
+                beneficiaries,
+                rate_per_second,
+                start,
+                last_claim: Map::new(env),
+                claimed: Map::new(env),
+            },
+        );
+    }
+
+    /// Claims everything the beneficiary's stream has accrued since their last claim:
+    /// `rate_per_second` times the elapsed seconds, capped at their remaining allocation.
+    ///
+    /// Emits a `claim` event whose data is `(amount, remaining_total)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary claiming the funds.
+    ///
+    /// # Returns
+    ///
+    /// The amount transferred to the beneficiary.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `beneficiary` is not in the list of beneficiaries.
+    /// * If the beneficiary claimed within the minimum claim interval.
+    /// * If nothing has accrued since the last claim.
+    pub fn claim_stream(env: &Env, beneficiary: Address) -> i128 {
+        beneficiary.require_auth();
+        check_no_upgrade(env);
+        extend_instance_ttl(env);
+        let mut stream: StreamedBalance = env.storage().instance().get(&DataKey::Stream).unwrap();
+        check_token(env, &stream.token);
+
+        if !stream.beneficiaries.contains(&beneficiary) {
+            panic!("beneficiary not in list");
+        }
+        check_not_blacklisted(env, &beneficiary);
+        check_claim_interval(env, &beneficiary);
+        let now = env.ledger().timestamp();
+        let last_claim = stream
+            .last_claim
+            .get(beneficiary.clone())
+            .unwrap_or(stream.start);
+        let claimed = stream.claimed.get(beneficiary.clone()).unwrap_or(0);
+        let amount = (now.saturating_sub(last_claim) as i128)
+            .checked_mul(stream.rate_per_second)
+            .unwrap_or(i128::MAX)
+            .min(stream.amount_per_beneficiary - claimed);
+        if amount <= 0 {
+            panic!("nothing to claim");
+        }
+
+        stream.last_claim.set(beneficiary.clone(), now);
+        stream.claimed.set(beneficiary.clone(), claimed + amount);
+        stream.total_amount -= amount;
+        pay_out(env, &stream.token, &beneficiary, amount);
+
+        if stream.total_amount == 0 {
+            env.storage().instance().remove(&DataKey::Stream);
+        } else {
+            env.storage().instance().set(&DataKey::Stream, &stream);
+        }
+        log_claim(env, &beneficiary, amount);
+        env.events().publish(
+            (symbol_short!("claim"), beneficiary),
+            (amount, stream.total_amount),
+        );
+        amount
+    }
+
+    /// Deposits `total` split among the beneficiaries by weight, initializing the contract.
+    ///
+    /// Each share is `total * weight / 10000`, with the rounding remainder handed out by
+    /// largest remainder so the shares sum exactly to `total` after any fee.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `from` - The address from which the funds are being deposited.
+    /// * `token` - The address of the token being deposited.
+    /// * `total` - The total amount of tokens deposited.
+    /// * `beneficiaries` - The list of beneficiary addresses.
+    /// * `weights_bps` - Each beneficiary's weight in basis points, summing to 10000.
+    /// * `timebound` - The timebound for claiming the funds.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `total` is not positive, or a share is above the configured maximum.
+    /// * If `weights_bps` is not one non-zero weight per beneficiary, summing to 10000.
+    /// * If the number of `beneficiaries` exceeds 10.
+    /// * If a `Before` time bound is not in the future.
+    /// * If the contract has already been initialized.
+    pub fn deposit_weighted(
+        env: &Env,
+        from: Address,
+        token: Address,
+        total: i128,
+        beneficiaries: Vec<Address>,
+        weights_bps: Vec<u32>,
+        timebound: TimeBound,
+    ) {
+        if total <= 0 {
+            panic!("amount must be positive");
+        }
+        if weights_bps.len() != beneficiaries.len()
+            || weights_bps.contains(0)
+            || weights_bps.iter().sum::<u32>() != 10_000
+        {
+            panic!("invalid weights");
+        }
+
+        let largest_share = weighted_shares(env, total, &weights_bps)
+            .iter()
+            .max()
+            .unwrap();
+        check_deposit(env, largest_share, &beneficiaries, true);
+
+        check_not_expired(env, &timebound);
+
+        from.require_auth();
+
+        let fee = deposit_fee(env, total, None);
+        start_distribution(env, &from, &token, beneficiaries.len(), total, fee, false);
+        let shares = weighted_shares(env, total - fee, &weights_bps);
+        env.events().publish(
+            (symbol_short!("weighted"), from),
+            (token.clone(), shares.clone(), beneficiaries.clone()),
+        );
+        env.storage().instance().set(
+            &DataKey::Weighted,
+            &WeightedBalance {
+                token,
+                total_amount: total - fee,
+                beneficiaries,
+                shares,
+                claimed_beneficiaries: Vec::new(env),
+                time_bound: timebound,
+            },
+        );
+    }
+
+    /// Claims the beneficiary's share of the weighted balance.
+    ///
+    /// Emits a `claim` event whose data is `(amount, remaining_total)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary claiming the funds.
+    ///
+    /// # Returns
+    ///
+    /// The amount transferred to the beneficiary.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If `beneficiary` is not in the list of beneficiaries.
+    /// * If the beneficiary claimed within the minimum claim interval.
+    /// * If the time bound for claiming the funds is not satisfied.
+    /// * If the beneficiary has already claimed.
+    pub fn claim_weighted(env: &Env, beneficiary: Address) -> i128 {
+        beneficiary.require_auth();
+        check_no_upgrade(env);
+        extend_instance_ttl(env);
+        let mut weighted: WeightedBalance =
+            env.storage().instance().get(&DataKey::Weighted).unwrap();
+        check_token(env, &weighted.token);
+
+        let index = weighted
+            .beneficiaries
+            .first_index_of(&beneficiary)
+            .unwrap_or_else(|| panic!("beneficiary not in list"));
+        check_not_blacklisted(env, &beneficiary);
+        check_claim_interval(env, &beneficiary);
+        require_time_bound(env, &weighted.time_bound);
+        if weighted.claimed_beneficiaries.contains(&beneficiary) {
+            panic!("beneficiary already claimed");
+        }
+
+        let amount = weighted.shares.get(index).unwrap();
+        weighted
+            .claimed_beneficiaries
+            .push_back(beneficiary.clone());
+        weighted.total_amount -= amount;
+        pay_out(env, &weighted.token, &beneficiary, amount);
+
+        if weighted.claimed_beneficiaries.len() == weighted.beneficiaries.len() {
+            env.storage().instance().remove(&DataKey::Weighted);
+        } else {
+            env.storage().instance().set(&DataKey::Weighted, &weighted);
+        }
+        log_claim(env, &beneficiary, amount);
+        env.events().publish(
+            (symbol_short!("claim"), beneficiary),
+            (amount, weighted.total_amount),
+        );
+        amount
+    }
+
+    /// Returns the timestamp at which the final tranche of the schedule unlocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is no active schedule.
+    pub fn vesting_complete_at(env: &Env) -> u64 {
+        let schedule: ScheduledBalance = env
+            .storage()
+            .instance()
+            .get(&DataKey::Schedule)
+            .unwrap_or_else(|| panic!("no active schedule"));
+        schedule.tranches.last().unwrap().unlock_timestamp
+    }
+
+    /// Returns the unlocked share of the schedule in basis points, from 0 before the first
+    /// tranche to 10000 once the final tranche has unlocked.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is no active schedule.
+    pub fn vesting_progress(env: &Env) -> u32 {
+        let schedule: ScheduledBalance = env
+            .storage()
+            .instance()
+            .get(&DataKey::Schedule)
+            .unwrap_or_else(|| panic!("no active schedule"));
+        schedule
+            .tranches
+            .iter()
+            .filter(|tranche| tranche.unlock_timestamp <= env.ledger().timestamp())
+            .map(|tranche| tranche.fraction_bps)
+            .sum()
+    }
+
+    /// Splits the unclaimed shares of an expired balance among the beneficiaries who claimed.
+    ///
+    /// Only a `Before` time bound can expire. Each claimed beneficiary that is neither
+    /// blacklisted nor renounced receives an equal share of the remaining `total_amount`,
+    /// excluding shares owed to pending beneficiaries; any integer remainder is left in the
+    /// contract as dust for a later [`Self::sweep`]. The balance is removed afterwards unless
+    /// pending beneficiaries remain.
+    ///
+    /// Shares are paid like claims: credited when claims are credited, and with deferred
+    /// transfers a failed share is credited instead of blocking the others. Each share is
+    /// recorded in the claim log and emits a `redistrib` event for its recipient.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If an upgrade is pending.
+    /// * If the time bound has not expired.
+    /// * If no beneficiary has claimed, ignoring blacklisted and renounced ones.
+    pub fn redistribute(env: &Env) {
+        check_no_upgrade(env);
+        let mut claimable_balance = load_balance(env);
+
+        let expired = matches!(claimable_balance.time_bound.kind, TimeBoundKind::Before)
+            && !check_time_bound(env, &claimable_balance.time_bound);
+        if !expired {
+            panic!("time bound not expired");
+        }
+        let renounced = renounced(env);
+        let mut recipients = Vec::new(env);
+        for beneficiary in claimable_balance.claimed_beneficiaries.iter() {
+            if !is_blacklisted(env, &beneficiary) && !renounced.contains(&beneficiary) {
+                recipients.push_back(beneficiary);
+            }
+        }
+        if recipients.is_empty() {
+            panic!("no claimed beneficiaries");
+        }
+
+        let pending_total = claimable_balance.amount_per_beneficiary
+            * claimable_balance.pending_beneficiaries.len() as i128;
+        let share = (claimable_balance.total_amount - pending_total) / recipients.len() as i128;
+        for beneficiary in recipients.iter() {
+            pay_out_or_credit(
+                env,
+                &claimable_balance.token,
+                &beneficiary,
+                share,
+                claimable_balance.defer_failed_transfers,
+            );
+            log_claim(env, &beneficiary, share);
+            env.events()
+                .publish((symbol_short!("redistrib"), beneficiary), share);
+        }
+        if claimable_balance.pending_beneficiaries.is_empty() {
+            clear_pending_claims(env, &claimable_balance.beneficiaries);
+            env.storage().instance().remove(&DataKey::Balance);
+            env.storage().instance().remove(&DataKey::Renounced);
+            env.storage().instance().remove(&DataKey::RenouncedTotal);
+        } else {
+            claimable_balance.total_amount = pending_total;
+            env.storage()
+                .instance()
+                .set(&DataKey::Balance, &claimable_balance);
+        }
+    }
+
+    /// Removes a balance whose beneficiaries have all claimed and emits a `finalize` event.
+    ///
+    /// Claims normally remove the balance on the final payout; this is a deterministic
+    /// cleanup hook for flows that bypass that path. Callable by anyone.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If there is no active balance.
+    /// * If some beneficiaries have not claimed yet.
+    pub fn finalize(env: &Env) {
+        let claimable_balance: ClaimableBalance = env
+            .storage()
+            .instance()
+            .get(&DataKey::Balance)
+            .unwrap_or_else(|| panic!("no active balance"));
+        check_token(env, &claimable_balance.token);
+        if claimable_balance.claimed_beneficiaries.len() != claimable_balance.beneficiaries.len() {
+            panic!("distribution not complete");
+        }
+        env.storage().instance().remove(&DataKey::Balance);
+        env.events().publish((symbol_short!("finalize"),), ());
+    }
+
+    /// Sets whether claims on the balance are released, e.g. once a legal sign-off is given.
+    ///
+    /// Claims require both the time bound and the release; this only matters for balances
+    /// deposited with `requires_release`.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `released` - Whether claims are allowed.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is no active balance.
+    pub fn set_released(env: &Env, released: bool) {
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        let mut claimable_balance = load_balance(env);
+        claimable_balance.released = released;
+        env.storage()
+            .instance()
+            .set(&DataKey::Balance, &claimable_balance);
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("released")),
+            released,
+        );
+    }
+
+    /// Sets whether claims require the depositor to co-sign alongside the beneficiary, for
+    /// dual-control release of high-value balances.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `co_sign` - Whether claims require the depositor's authorization.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is no active balance.
+    pub fn set_co_sign(env: &Env, co_sign: bool) {
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        let mut claimable_balance = load_balance(env);
+        claimable_balance.co_sign = co_sign;
+        env.storage()
+            .instance()
+            .set(&DataKey::Balance, &claimable_balance);
+        env.events()
+            .publish((symbol_short!("config"), symbol_short!("co_sign")), co_sign);
+    }
+
+    /// Attaches a human-readable description to the distribution, such as "Q1 2025 Grants", for
+    /// indexers and explorers to display.
+    ///
+    /// The description can also be set at deposit time through [`Self::deposit_config`].
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `description` - The description, at most 64 bytes.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If the contract has not been initialized.
+    /// * If `description` is longer than 64 bytes.
+    pub fn set_description(env: &Env, description: String) {
+        if !is_initialized(env) {
+            panic!("not initialized");
+        }
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        if description.len() > MAX_DESCRIPTION_LEN {
+            panic!("description too long");
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::Description, &description);
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("desc")),
+            description,
+        );
+    }
+
+    /// Sets the contract that issues a receipt for each claim, or clears it with `None`.
+    ///
+    /// After every successful claim the contract calls `mint(beneficiary)` on it. Receipts are
+    /// best-effort: a failing attestation contract does not revert the claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `attestation_contract` - The address of the attestation contract.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the contract has not been initialized.
+    pub fn set_attestation_contract(env: &Env, attestation_contract: Option<Address>) {
+        if !is_initialized(env) {
+            panic!("not initialized");
+        }
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("attest")),
+            attestation_contract.clone(),
+        );
+        match attestation_contract {
+            Some(attestation_contract) => env
+                .storage()
+                .instance()
+                .set(&DataKey::Attestation, &attestation_contract),
+            None => env.storage().instance().remove(&DataKey::Attestation),
+        }
+    }
+
+    /// Forfeits a beneficiary's share without paying it out.
+    ///
+    /// The beneficiary is marked as claimed and `total_amount` is reduced, so the freed share
+    /// becomes excess the depositor can recover with [`Self::sweep`]. Emits a `renounce`
+    /// event with the forfeited amount. Renounced beneficiaries take no part in
+    /// [`Self::redistribute`].
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary giving up their share.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If there is no active balance.
+    /// * If the beneficiary is not in the list of beneficiaries.
+    /// * If the beneficiary has already claimed.
+    pub fn renounce(env: &Env, beneficiary: Address) {
+        beneficiary.require_auth();
+        let mut claimable_balance = load_balance(env);
+        if !claimable_balance.beneficiaries.contains(&beneficiary) {
+            panic!("beneficiary not in list");
+        }
+        if claimable_balance
+            .claimed_beneficiaries
+            .contains(&beneficiary)
+        {
+            panic!("beneficiary already claimed");
+        }
+
+        if let Some(index) = claimable_balance
+            .pending_beneficiaries
+            .first_index_of(&beneficiary)
+        {
+            claimable_balance.pending_beneficiaries.remove(index);
+        }
+        claimable_balance
+            .claimed_beneficiaries
+            .push_back(beneficiary.clone());
+        claimable_balance.total_amount -= claimable_balance.amount_per_beneficiary;
+        env.storage()
+            .instance()
+            .remove(&DataKey::PendingClaim(beneficiary.clone()));
+        let mut renounced = renounced(env);
+        renounced.push_back(beneficiary.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::Renounced, &renounced);
+        let renounced_total: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RenouncedTotal)
+            .unwrap_or(0);
+        env.storage().instance().set(
+            &DataKey::RenouncedTotal,
+            &(renounced_total + claimable_balance.amount_per_beneficiary),
+        );
+        env.events().publish(
+            (symbol_short!("renounce"), beneficiary),
+            claimable_balance.amount_per_beneficiary,
+        );
+
+        save_balance(env, &claimable_balance);
+    }
+
+    /// Transfers any tokens held above the outstanding obligation back to the depositor,
+    /// emitting a `sweep` event with the amount.
+    ///
+    /// Deflationary tokens or direct transfers can leave the contract holding more than
+    /// `total_amount`; only that excess is swept, so beneficiary funds are never touched.
+    /// Once every beneficiary has claimed, the whole remaining token balance is excess.
+    /// Schedule tranches past their claim deadline are excess too, even for beneficiaries
+    /// who never claim.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Returns
+    ///
+    /// The amount transferred to the depositor.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If the contract has not been initialized.
+    /// * If there is no excess balance to sweep.
+    pub fn sweep(env: &Env) -> i128 {
+        if !is_initialized(env) {
+            panic!("not initialized");
+        }
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+
+        let excess = transfer_excess(env, 0);
+        if excess <= 0 {
+            panic!("nothing to sweep");
+        }
+        let renounced_total: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RenouncedTotal)
+            .unwrap_or(0);
+        if renounced_total > 0 {
+            env.storage()
+                .instance()
+                .set(&DataKey::RenouncedTotal, &(renounced_total - excess).max(0));
+        }
+        excess
+    }
+
+    /// Sets whether the final claim automatically refunds any excess to the depositor.
+    ///
+    /// The final claim always refunds tokens held above the outstanding obligation, such as
+    /// over-collection. When enabled, renounced shares not yet swept are refunded as well
+    /// instead of waiting for [`Self::sweep`].
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `auto_refund` - Whether to refund on the final claim.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the contract has not been initialized.
+    pub fn set_auto_refund(env: &Env, auto_refund: bool) {
+        if !is_initialized(env) {
+            panic!("not initialized");
+        }
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::AutoRefund, &auto_refund);
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("refund")),
+            auto_refund,
+        );
+    }
+
+    /// Sets whether claims credit an internal balance instead of transferring. Credited
+    /// amounts accrue per beneficiary until [`Self::withdraw`] pays them out in a single
+    /// transfer, which saves transfers when beneficiaries claim often.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `credit_claims` - Whether to credit claims.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the contract has not been initialized.
+    pub fn set_credit_claims(env: &Env, credit_claims: bool) {
+        if !is_initialized(env) {
+            panic!("not initialized");
+        }
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::CreditClaims, &credit_claims);
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("credit")),
+            credit_claims,
+        );
+    }
+
+    /// Returns the credit `beneficiary` has accrued and not yet withdrawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary.
+    pub fn credit(env: &Env, beneficiary: Address) -> i128 {
+        credits(env).get(beneficiary).unwrap_or(0)
+    }
+
+    /// Transfers the credit `beneficiary` has accrued from claims and emits a `withdraw`
+    /// event with the amount.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary withdrawing.
+    ///
+    /// # Returns
+    ///
+    /// The amount transferred.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `beneficiary` has no credit.
+    pub fn withdraw(env: &Env, beneficiary: Address) -> i128 {
+        beneficiary.require_auth();
+        let mut credits = credits(env);
+        let amount = credits.get(beneficiary.clone()).unwrap_or(0);
+        if amount <= 0 {
+            panic!("no credit");
+        }
+        credits.remove(beneficiary.clone());
+        env.storage().instance().set(&DataKey::Credits, &credits);
+
+        let token: Address = env.storage().instance().get(&DataKey::Token).unwrap();
+        token::Client::new(env, &token).transfer(
+            &env.current_contract_address(),
+            &beneficiary,
+            &amount,
+        );
+        env.events()
+            .publish((symbol_short!("withdraw"), beneficiary), amount);
+        amount
+    }
+
+    /// Sets the minimum number of ledgers between a beneficiary's claims in the streaming
+    /// and tranche modes, or removes the limit with `None`. Unlike a wall-clock cooldown,
+    /// this is counted in ledger sequence numbers.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `ledgers` - The minimum number of ledgers between claims.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the contract has not been initialized.
+    pub fn set_min_claim_interval(env: &Env, ledgers: Option<u32>) {
+        if !is_initialized(env) {
+            panic!("not initialized");
+        }
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        match ledgers {
+            Some(ledgers) => env
+                .storage()
+                .instance()
+                .set(&DataKey::MinClaimInterval, &ledgers),
+            None => env.storage().instance().remove(&DataKey::MinClaimInterval),
+        }
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("interval")),
+            ledgers,
+        );
+    }
+
+    /// Returns the contract's balance of the distribution token.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the contract has not been initialized.
+    pub fn contract_balance(env: &Env) -> i128 {
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic!("not initialized"));
+        token::Client::new(env, &token).balance(&env.current_contract_address())
+    }
+
+    /// Returns a proxy for the storage footprint of the active distribution: the number of
+    /// stored beneficiary entries plus claimed and pending entries, or 0 if none is active.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    pub fn footprint(env: &Env) -> u32 {
+        if let Some(balance) = env
+            .storage()
+            .instance()
+            .get::<_, ClaimableBalance>(&DataKey::Balance)
+        {
+            return balance.beneficiaries.len()
+                + balance.claimed_beneficiaries.len()
+                + balance.pending_beneficiaries.len();
+        }
+        if let Some(pool) = env
+            .storage()
+            .instance()
+            .get::<_, PooledBalance>(&DataKey::Pool)
+        {
+            return pool.beneficiaries.len() + pool.remaining_caps.len();
+        }
+        if let Some(schedule) = env
+            .storage()
+            .instance()
+            .get::<_, ScheduledBalance>(&DataKey::Schedule)
+        {
+            return schedule.beneficiaries.len() + schedule.claimed_tranches.len();
+        }
+        if let Some(weighted) = env
+            .storage()
+            .instance()
+            .get::<_, WeightedBalance>(&DataKey::Weighted)
+        {
+            return weighted.beneficiaries.len() + weighted.claimed_beneficiaries.len();
+        }
+        env.storage()
+            .instance()
+            .get::<_, StreamedBalance>(&DataKey::Stream)
+            .map_or(0, |stream| {
+                stream.beneficiaries.len() + stream.last_claim.len()
+            })
+    }
+
+    /// Returns the active claimable balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is no active balance.
+    pub fn get_balance(env: &Env) -> ClaimableBalance {
+        env.storage()
+            .instance()
+            .get(&DataKey::Balance)
+            .unwrap_or_else(|| panic!("no active balance"))
+    }
+
+    /// Returns the kind of the active distribution's time bound as `before`, `after` or
+    /// `none`.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is no active balance, weighted balance or pool.
+    pub fn time_bound_kind(env: &Env) -> Symbol {
+        let time_bound = if let Some(balance) = env
+            .storage()
+            .instance()
+            .get::<_, ClaimableBalance>(&DataKey::Balance)
+        {
+            balance.time_bound
+        } else if let Some(weighted) = env
+            .storage()
+            .instance()
+            .get::<_, WeightedBalance>(&DataKey::Weighted)
+        {
+            weighted.time_bound
+        } else {
+            env.storage()
+                .instance()
+                .get::<_, PooledBalance>(&DataKey::Pool)
+                .unwrap_or_else(|| panic!("no active balance"))
+                .time_bound
+        };
+        match time_bound.kind {
+            TimeBoundKind::Before => symbol_short!("before"),
+            TimeBoundKind::After => symbol_short!("after"),
+            TimeBoundKind::None => symbol_short!("none"),
+        }
+    }
+
+    /// Returns the `index`-th entry of the claim log as `(beneficiary, timestamp)`.
+    ///
+    /// Every payout is appended to the log, which survives removal of the balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `index` - The position of the claim in the log, starting at 0.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `index` is out of range.
+    pub fn claim_at(env: &Env, index: u32) -> (Address, u64) {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ClaimLog(index))
+            .unwrap_or_else(|| panic!("claim index out of range"))
+    }
+
+    /// Returns how many beneficiaries the distribution was created with.
+    ///
+    /// Unlike the balance itself, this count is kept after every beneficiary has claimed.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the contract has not been initialized.
+    pub fn total_beneficiaries_ever(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::BeneficiaryCount)
+            .unwrap_or_else(|| panic!("not initialized"))
+    }
+
+    /// Returns the address that funded the distribution.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the contract has not been initialized.
+    pub fn get_depositor(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Depositor)
+            .unwrap_or_else(|| panic!("not initialized"))
+    }
+
+    /// Returns the ledger timestamp at which the distribution was funded.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the contract has not been initialized.
+    pub fn deposit_time(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DepositTime)
+            .unwrap_or_else(|| panic!("not initialized"))
+    }
+
+    /// Returns the cumulative amount paid out to `beneficiary` across all of their claims.
+    ///
+    /// Beneficiaries who have never claimed, including non-beneficiaries, get 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address of the beneficiary.
+    pub fn claimed_amount_for(env: &Env, beneficiary: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get::<_, Map<Address, i128>>(&DataKey::ClaimedAmounts)
+            .and_then(|claimed_amounts| claimed_amounts.get(beneficiary))
+            .unwrap_or(0)
+    }
+
+    /// Returns a single status for `beneficiary`, suitable for one row of a UI.
+    ///
+    /// The status is one of `claimed`, `pending` (a deferred transfer awaits retry),
+    /// `blocked` (the address is blacklisted), `locked` (the time bound is not satisfied),
+    /// `frozen` (waiting for release), `claimable`, or `none` when the address is not a
+    /// beneficiary.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address to look up.
+    pub fn beneficiary_status(env: &Env, beneficiary: Address) -> Symbol {
+        let claimable_balance: ClaimableBalance =
+            match env.storage().instance().get(&DataKey::Balance) {
+                Some(claimable_balance) => claimable_balance,
+                None if Self::claimed_amount_for(env, beneficiary) > 0 => {
+                    return symbol_short!("claimed")
+                }
+                None => return symbol_short!("none"),
+            };
+
+        if !claimable_balance.beneficiaries.contains(&beneficiary) {
+            symbol_short!("none")
+        } else if claimable_balance
+            .claimed_beneficiaries
+            .contains(&beneficiary)
+        {
+            symbol_short!("claimed")
+        } else if is_blacklisted(env, &beneficiary) {
+            symbol_short!("blocked")
+        } else if claimable_balance
+            .pending_beneficiaries
+            .contains(&beneficiary)
+        {
+            symbol_short!("pending")
+        } else if !check_time_bound(env, &claimable_balance.time_bound) {
+            symbol_short!("locked")
+        } else if !claimable_balance.released {
+            symbol_short!("frozen")
+        } else {
+            symbol_short!("claimable")
+        }
+    }
+
+    /// Sets the contract administrator. Deployments that need no administrator may skip
+    /// this, in which case admin-gated functions are unavailable. Once a deposit has been
+    /// made, the depositor must also authorize, so a stranger cannot take the role later.
+    ///
+    /// Before any deposit, the first caller becomes administrator, so a stranger could take
+    /// the role on a fresh deployment. Deposits therefore refuse to proceed until the
+    /// depositor has accepted the administrator with [`Self::approve_admin`].
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `admin` - The address of the administrator.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if an administrator has already been set.
+    pub fn init_admin(env: &Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("admin already set");
+        }
+        admin.require_auth();
+        if let Some(depositor) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::Depositor)
+        {
+            depositor.require_auth();
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.events().publish((symbol_short!("admin"),), admin);
+    }
+
+    /// Approves the current administrator for deposits by `depositor`. Once an
+    /// administrator is set, every deposit that initializes the contract requires this
+    /// approval, so a depositor never funds a contract run by an administrator they did not
+    /// accept. Approving a different administrator later replaces the approval.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `depositor` - The address that will deposit.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If no administrator has been set.
+    /// * If `depositor` has not authorized the call.
+    pub fn approve_admin(env: &Env, depositor: Address) {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic!("admin not set"));
+        depositor.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::AdminApproval(depositor.clone()), &admin);
+        env.events()
+            .publish((symbol_short!("approve"), depositor), admin);
+    }
+
+    /// Hands the administrator role over to `new_admin`. Requires the current
+    /// administrator's authorization.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `new_admin` - The address of the new administrator.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if no administrator has been set.
+    pub fn set_admin(env: &Env, new_admin: Address) {
+        require_admin(env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.events().publish((symbol_short!("admin"),), new_admin);
+    }
+
+    /// Returns the contract administrator.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if no administrator has been set.
+    pub fn get_admin(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic!("admin not set"))
+    }
+
+    /// Sets the largest `amount_per_beneficiary` a deposit may use, or removes the limit
+    /// with `None`. This is a guardrail against fat-finger amounts, such as too many
+    /// decimal places, not a protocol limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `max_amount` - The maximum amount per beneficiary.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If no administrator has been set.
+    /// * If `max_amount` is not positive.
+    pub fn set_max_amount(env: &Env, max_amount: Option<i128>) {
+        require_admin(env);
+        match max_amount {
+            Some(max_amount) if max_amount <= 0 => panic!("amount must be positive"),
+            Some(max_amount) => env
+                .storage()
+                .instance()
+                .set(&DataKey::MaxAmount, &max_amount),
+            None => env.storage().instance().remove(&DataKey::MaxAmount),
+        }
+        env.events().publish(
+            (symbol_short!("config"), symbol_short!("max_amt")),
+            max_amount,
+        );
+    }
+
+    /// Sets the protocol fee charged on future deposits of every kind, in basis points of
+    /// the deposited total, and the treasury it is sent to. The fee is taken out of each
+    /// beneficiary's share, or out of the pool, so the depositor pays the same total. A fee
+    /// of 0 disables it; balances already deposited are unaffected. Depositors can bound the
+    /// fee they accept with [`DepositConfig::max_fee_bps`].
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `fee_bps` - The fee in basis points.
+    /// * `treasury` - The address receiving the fees.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If no administrator has been set.
+    /// * If `fee_bps` exceeds 1000, i.e. 10%.
+    pub fn set_deposit_fee(env: &Env, fee_bps: u32, treasury: Address) {
+        require_admin(env);
+        if fee_bps > MAX_FEE_BPS {
+            panic!("invalid fee");
+        }
+        if fee_bps == 0 {
+            env.storage().instance().remove(&DataKey::ProtocolFeeBps);
+            env.storage().instance().remove(&DataKey::Treasury);
+        } else {
+            env.storage()
+                .instance()
+                .set(&DataKey::ProtocolFeeBps, &fee_bps);
+            env.storage().instance().set(&DataKey::Treasury, &treasury);
+        }
+        env.events()
+            .publish((symbol_short!("fee_rate"), treasury), fee_bps);
+    }
+
+    /// Adds `address` to the blacklist. Blacklisted addresses cannot be named as
+    /// beneficiaries of a deposit and cannot claim, even from a deposit made before they
+    /// were added.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `address` - The address to block.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if no administrator has been set.
+    pub fn blacklist_add(env: &Env, address: Address) {
+        require_admin(env);
+        let mut blacklist: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Blacklist)
+            .unwrap_or(Vec::new(env));
+        if !blacklist.contains(&address) {
+            blacklist.push_back(address.clone());
+            env.storage()
+                .instance()
+                .set(&DataKey::Blacklist, &blacklist);
+            env.events()
+                .publish((symbol_short!("blacklist"), address), true);
+        }
+    }
+
+    /// Removes `address` from the blacklist.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `address` - The address to unblock.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if no administrator has been set.
+    pub fn blacklist_remove(env: &Env, address: Address) {
+        require_admin(env);
+        let mut blacklist: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Blacklist)
+            .unwrap_or(Vec::new(env));
+        if let Some(index) = blacklist.first_index_of(&address) {
+            blacklist.remove(index);
+            env.storage()
+                .instance()
+                .set(&DataKey::Blacklist, &blacklist);
+            env.events()
+                .publish((symbol_short!("blacklist"), address), false);
+        }
+    }
+
+    /// Returns up to `limit` beneficiaries of the active balance, starting at `start`.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `start` - The index of the first beneficiary to return.
+    /// * `limit` - The maximum number of beneficiaries to return.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is no active balance.
+    pub fn beneficiaries_page(env: &Env, start: u32, limit: u32) -> Vec<Address> {
+        let claimable_balance: ClaimableBalance = env
+            .storage()
+            .instance()
+            .get(&DataKey::Balance)
+            .unwrap_or_else(|| panic!("no active balance"));
+        let len = claimable_balance.beneficiaries.len();
+        let end = start.saturating_add(limit).min(len);
+        claimable_balance.beneficiaries.slice(start.min(end)..end)
+    }
+
+    /// Returns the beneficiaries of the active balance who have not claimed yet.
+    ///
+    /// The result is always in deposit order, whatever order the claims happened in, so
+    /// clients can rely on its indices being stable between calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is no active balance.
+    pub fn beneficiaries_remaining(env: &Env) -> Vec<Address> {
+        let claimable_balance = Self::get_balance(env);
+        let mut remaining = Vec::new(env);
+        for beneficiary in claimable_balance.beneficiaries.iter() {
+            if !claimable_balance
+                .claimed_beneficiaries
+                .contains(&beneficiary)
+            {
+                remaining.push_back(beneficiary);
+            }
+        }
+        remaining
+    }
+
+    /// Returns `(beneficiary, status, owed)` for up to `limit` beneficiaries of the active
+    /// balance, starting at `start`, where `status` is as in [`Self::beneficiary_status`]
+    /// and `owed` is the amount still due to the beneficiary.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `start` - The index of the first beneficiary to return.
+    /// * `limit` - The maximum number of beneficiaries to return.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is no active balance.
+    pub fn page_details(env: &Env, start: u32, limit: u32) -> Vec<(Address, Symbol, i128)> {
+        let amount_per_beneficiary = Self::get_balance(env).amount_per_beneficiary;
+        let mut details = Vec::new(env);
+        for beneficiary in Self::beneficiaries_page(env, start, limit).iter() {
+            let status = Self::beneficiary_status(env, beneficiary.clone());
+            let owed = if status == symbol_short!("claimed") {
+                0
+            } else {
+                amount_per_beneficiary
+            };
+            details.push_back((beneficiary, status, owed));
+        }
+        details
+    }
+
+    /// Transfers the contract's whole balance of an unrelated `token`, sent to it by
+    /// mistake, to `to` and emits a `rescue` event with the amount.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `token` - The address of the token to rescue.
+    /// * `to` - The address receiving the rescued tokens.
+    ///
+    /// # Returns
+    ///
+    /// The amount transferred.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If no administrator has been set.
+    /// * If `token` is the distribution token.
+    pub fn rescue_token(env: &Env, token: Address, to: Address) -> i128 {
+        require_admin(env);
+        if env.storage().instance().get::<_, Address>(&DataKey::Token) == Some(token.clone()) {
+            panic!("cannot rescue distribution token");
+        }
+
+        let token_client = token::Client::new(env, &token);
+        let amount = token_client.balance(&env.current_contract_address());
+        token_client.transfer(&env.current_contract_address(), &to, &amount);
+        env.events()
+            .publish((symbol_short!("rescue"), token), amount);
+        amount
+    }
+
+    /// Halts the active distribution, for example on a compliance takedown: the balance,
+    /// pool or schedule is removed so no further claims succeed, the contract's distribution
+    /// token balance is transferred to `to`, and a `revoked` event is emitted with the
+    /// amount. Credits already accrued are not revoked and can still be withdrawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `to` - The address receiving the refund.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If no administrator has been set.
+    /// * If the contract has not been initialized.
+    /// * If the depositor made the distribution irrevocable.
+    pub fn revoke_all(env: &Env, to: Address) {
+        require_admin(env);
+        if env
+            .storage()
+            .instance()
+            .get(&DataKey::Irrevocable)
+            .unwrap_or(false)
+        {
+            panic!("irrevocable");
+        }
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic!("not initialized"));
+
+        if let Some(claimable_balance) = env
+            .storage()
+            .instance()
+            .get::<_, ClaimableBalance>(&DataKey::Balance)
+        {
+            clear_pending_claims(env, &claimable_balance.beneficiaries);
+        }
+        env.storage().instance().remove(&DataKey::Balance);
+        env.storage().instance().remove(&DataKey::Pool);
+        env.storage().instance().remove(&DataKey::Schedule);
+        env.storage().instance().remove(&DataKey::Stream);
+        env.storage().instance().remove(&DataKey::Weighted);
+        env.storage().instance().remove(&DataKey::Renounced);
+        env.storage().instance().remove(&DataKey::RenouncedTotal);
+        let token_client = token::Client::new(env, &token);
+        let credited: i128 = credits(env).values().iter().sum();
+        let amount = token_client.balance(&env.current_contract_address()) - credited;
+        if amount > 0 {
+            token_client.transfer(&env.current_contract_address(), &to, &amount);
+        }
+        env.events().publish((symbol_short!("revoked"), to), amount);
+    }
+
+    /// Makes the distribution irrevocable, so [`Self::revoke_all`] can no longer halt it.
+    ///
+    /// Deposits through [`Self::deposit_config`] should set `irrevocable` instead, which
+    /// leaves no window for a revocation; this serves the other deposit paths. This cannot
+    /// be undone. Emits a `config` event.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If the contract has not been initialized.
+    /// * If the depositor has not authorized the call.
+    pub fn make_irrevocable(env: &Env) {
+        if !is_initialized(env) {
+            panic!("not initialized");
+        }
+        let depositor: Address = env.storage().instance().get(&DataKey::Depositor).unwrap();
+        depositor.require_auth();
+        env.storage().instance().set(&DataKey::Irrevocable, &true);
+        env.events()
+            .publish((symbol_short!("config"), symbol_short!("irrevoc")), true);
+    }
+
+    /// Marks an upgrade as pending, blocking deposits and claims until [`Self::upgrade`]
+    /// completes it or [`Self::cancel_upgrade`] clears it.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if no administrator has been set.
+    pub fn schedule_upgrade(env: &Env) {
+        require_admin(env);
+        env.storage()
+            .instance()
+            .set(&DataKey::UpgradePending, &true);
+        env.events().publish((symbol_short!("upgrade"),), true);
+    }
+
+    /// Clears a pending upgrade, re-enabling deposits and claims.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if no administrator has been set.
+    pub fn cancel_upgrade(env: &Env) {
+        require_admin(env);
+        env.storage().instance().remove(&DataKey::UpgradePending);
+        env.events().publish((symbol_short!("upgrade"),), false);
+    }
+
+    /// Replaces the contract code with `new_wasm_hash` and clears the pending upgrade.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `new_wasm_hash` - The hash of the uploaded wasm to switch to.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If no administrator has been set.
+    /// * If no upgrade has been scheduled.
+    pub fn upgrade(env: &Env, new_wasm_hash: BytesN<32>) {
+        require_admin(env);
+        if !env.storage().instance().has(&DataKey::UpgradePending) {
+            panic!("no upgrade scheduled");
+        }
+        env.storage().instance().remove(&DataKey::UpgradePending);
+        env.events()
+            .publish((symbol_short!("upgraded"),), new_wasm_hash.clone());
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    /// Returns the distribution token, read from its own storage key without loading the
+    /// balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the contract has not been initialized.
+    pub fn get_token(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic!("not initialized"))
+    }
+
+    /// Returns whether `beneficiary` could claim right now, i.e. their
+    /// [`Self::beneficiary_status`] is `claimable` or `pending`.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `beneficiary` - The address to check.
+    pub fn is_claimable(env: &Env, beneficiary: Address) -> bool {
+        let status = Self::beneficiary_status(env, beneficiary);
+        status == symbol_short!("claimable") || status == symbol_short!("pending")
+    }
+
+    /// Returns [`Self::is_claimable`] for each of `addrs`, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `addrs` - The addresses to check.
+    pub fn claimable_batch(env: &Env, addrs: Vec<Address>) -> Vec<bool> {
+        let mut claimable = Vec::new(env);
+        for addr in addrs.iter() {
+            claimable.push_back(Self::is_claimable(env, addr));
+        }
+        claimable
+    }
+
+    /// Returns the amount still owed to beneficiaries who have not claimed, or 0 when there
+    /// is no active balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    pub fn remaining_total(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get::<_, ClaimableBalance>(&DataKey::Balance)
+            .map_or(0, |claimable_balance| {
+                claimable_balance.amount_per_beneficiary * unclaimed_count(env) as i128
+            })
+    }
+
+    /// Returns `(token, amount_per_beneficiary, total_count, claimed_count, time_bound)` for
+    /// the active balance, covering most UI needs without fetching the address lists.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there is no active balance.
+    pub fn summary(env: &Env) -> (Address, i128, u32, u32, TimeBound) {
+        let claimable_balance = Self::get_balance(env);
+        (
+            claimable_balance.token,
+            claimable_balance.amount_per_beneficiary,
+            claimable_balance.beneficiaries.len(),
+            claimable_balance.claimed_beneficiaries.len(),
+            claimable_balance.time_bound,
+        )
+    }
+
+    /// Rewrites a balance stored in the original layout into the current one.
+    ///
+    /// The new flags take their disabled defaults. The original layout recorded neither the
+    /// depositor nor a standalone token key, so the token is copied from the balance and the
+    /// administrator becomes the depositor.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// * If no administrator has been set.
+    /// * If the storage is already in the current layout.
+    /// * If there is no balance to migrate.
+    pub fn migrate(env: &Env) {
+        let admin = require_admin(env);
+        if env.storage().instance().has(&DataKey::Version) {
+            panic!("already migrated");
+        }
+        let old: ClaimableBalanceV1 = env
+            .storage()
+            .instance()
+            .get(&DataKey::Balance)
+            .unwrap_or_else(|| panic!("nothing to migrate"));
+
+        env.storage().instance().set(&DataKey::Token, &old.token);
+        if !env.storage().instance().has(&DataKey::Depositor) {
+            env.storage().instance().set(&DataKey::Depositor, &admin);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::BeneficiaryCount, &old.beneficiaries.len());
+        env.storage().instance().set(
+            &DataKey::Balance,
+            &ClaimableBalance {
+                token: old.token,
+                amount_per_beneficiary: old.amount_per_beneficiary,
+                total_amount: old.total_amount,
+                beneficiaries: old.beneficiaries,
+                claimed_beneficiaries: old.claimed_beneficiaries,
+                time_bound: old.time_bound,
+                public_claim: false,
+                defer_failed_transfers: false,
+                pending_beneficiaries: Vec::new(env),
+                ordered: false,
+                released: true,
+                co_sign: false,
+            },
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &STORAGE_VERSION);
+        env.events()
+            .publish((symbol_short!("migrate"),), STORAGE_VERSION);
+    }
+
+    /// Returns the protocol version of the current ledger.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    pub fn protocol(env: &Env) -> u32 {
+        env.ledger().protocol_version()
+    }
+
+    /// Extends the contract instance's TTL so it lives at least `ledgers` more ledgers.
+    /// Callable by anyone; the extension is capped at 30 days of ledgers.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    /// * `ledgers` - The number of ledgers the instance should live for.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `ledgers` exceeds the cap.
+    pub fn extend_ttl(env: &Env, ledgers: u32) {
+        if ledgers > MAX_TTL_EXTENSION {
+            panic!("extension too large");
+        }
+        env.storage().instance().extend_ttl(ledgers, ledgers);
+    }
+
+    /// Returns the address of this contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    pub fn contract_address(env: &Env) -> Address {
+        env.current_contract_address()
+    }
+
+    /// Returns the description set with [`Self::set_description`], or an empty string if
+    /// there is none.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The contract environment.
+    pub fn get_description(env: &Env) -> String {
+        env.storage()
+            .instance()
+            .get(&DataKey::Description)
+            .unwrap_or_else(|| String::from_str(env, ""))
     }
 }
 
@@ -4,8 +4,14 @@ extern crate std;
 use core::ops::Add;
 
 use super::*;
-use soroban_sdk::testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger};
-use soroban_sdk::{symbol_short, token, vec, Address, Env, IntoVal};
+use soroban_sdk::testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events, Ledger, MockAuth, MockAuthInvoke};
+use soroban_sdk::auth::{Context, CustomAccountInterface};
+use soroban_sdk::xdr::{
+    ContractDataDurability, InvokeContractArgs, LedgerKey, LedgerKeyContractData, ScAddress, ScVal,
+    SorobanAddressCredentials, SorobanAuthorizationEntry, SorobanAuthorizedFunction,
+    SorobanAuthorizedInvocation, SorobanCredentials, VecM,
+};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, vec, Address, BytesN, Env, IntoVal, String, Symbol};
 use token::Client as TokenClient;
 use token::StellarAssetClient as TokenAdminClient;
 
@@ -18,6 +24,79 @@ fn create_claimable_contract<'a>(env: &Env) -> MultiPartyClaimableBalanceContrac
     MultiPartyClaimableBalanceContractClient::new(env, &env.register_contract(None, MultiPartyClaimableBalanceContract {}))
 }
 
+#[contracttype]
+enum FreezableTokenKey {
+    Balance(Address),
+    Frozen,
+    FrozenAccount(Address),
+}
+
+/// Minimal token whose transfers fail while frozen, to exercise claim transfer failures.
+#[contract]
+struct FreezableToken;
+
+#[contractimpl]
+impl FreezableToken {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().instance().set(&FreezableTokenKey::Balance(to), &(balance + amount));
+    }
+
+    pub fn set_frozen(env: Env, frozen: bool) {
+        env.storage().instance().set(&FreezableTokenKey::Frozen, &frozen);
+    }
+
+    pub fn freeze_account(env: Env, id: Address) {
+        env.storage().instance().set(&FreezableTokenKey::FrozenAccount(id), &true);
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&FreezableTokenKey::Balance(id)).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        if env.storage().instance().get(&FreezableTokenKey::Frozen).unwrap_or(false)
+            || env.storage().instance().has(&FreezableTokenKey::FrozenAccount(to.clone()))
+        {
+            panic!("account frozen");
+        }
+        from.require_auth();
+        let from_balance = Self::balance(env.clone(), from.clone());
+        env.storage().instance().set(&FreezableTokenKey::Balance(from), &(from_balance - amount));
+        Self::mint(env, to, amount);
+    }
+}
+
+/// Smart-contract wallet that approves every authorization it is asked to check.
+#[contract]
+struct TestWallet;
+
+#[contractimpl]
+impl CustomAccountInterface for TestWallet {
+    type Signature = ();
+    type Error = soroban_sdk::Error;
+
+    #[allow(non_snake_case)]
+    fn __check_auth(
+        _env: Env,
+        _signature_payload: BytesN<32>,
+        _signatures: (),
+        _auth_contexts: soroban_sdk::Vec<Context>,
+    ) -> Result<(), soroban_sdk::Error> {
+        Ok(())
+    }
+}
+
+fn instance_live_until(env: &Env, contract: &Address) -> u32 {
+    let key = LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ScAddress::try_from(contract).unwrap(),
+        key: ScVal::LedgerKeyContractInstance,
+        durability: ContractDataDurability::Persistent,
+    });
+    let snapshot = env.to_ledger_snapshot();
+    let (_, (_, live_until)) = snapshot.ledger_entries.iter().find(|(k, _)| **k == key).unwrap();
+    live_until.unwrap()
+}
 
 struct ClaimableBalanceTest <'a> {
     env: Env,
@@ -51,6 +130,12 @@ impl ClaimableBalanceTest <'_>{
     }
 }
 
+/// Returns a deposit of 100 of the test token to each of `beneficiaries`, with every
+/// optional claim mode disabled.
+fn config(test: &ClaimableBalanceTest, beneficiaries: soroban_sdk::Vec<Address>, timebound: TimeBound) -> DepositConfig {
+    default_config(&test.env, test.token.address.clone(), 100, beneficiaries, timebound)
+}
+
 #[test]
 fn test_deposit_and_claim() {
     let test = ClaimableBalanceTest::setup();
@@ -248,7 +333,7 @@ fn test_rogue_claimant_fail() {
 }
 
 #[test]
-#[should_panic(expected = "time bound not satisfied")]
+#[should_panic(expected = "claim not yet open")]
 fn test_bad_time_fail() {
     let test = ClaimableBalanceTest::setup();
     test.contract.deposit(
@@ -277,4 +362,2607 @@ fn test_negative_deposit_fail() {
     let test = ClaimableBalanceTest::setup();
     test.contract.deposit(
         &test.deposit_address, &test.token.address, &-1, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_claim_with_memo() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim_with_memo(&test.claim_address[0], &symbol_short!("inv42"));
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+
+    let events = test.env.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &test.env,
+            (
+                test.contract.address.clone(),
+                (symbol_short!("claim"), test.claim_address[0].clone()).into_val(&test.env),
+                (100_i128, 0_i128, symbol_short!("inv42")).into_val(&test.env),
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_sweep_excess() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&test.contract.address, &50);
+    assert_eq!(test.token.balance(&test.contract.address), 250);
+
+    assert_eq!(test.contract.sweep(), 50);
+    assert_eq!(test.token.balance(&test.deposit_address), 850);
+    assert_eq!(test.token.balance(&test.contract.address), 200);
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_sweep_after_all_claims() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim(&test.claim_address[0]);
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&test.contract.address, &30);
+
+    assert_eq!(test.contract.sweep(), 30);
+    assert_eq!(test.token.balance(&test.deposit_address), 930);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "nothing to sweep")]
+fn test_sweep_beneficiary_funds_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.sweep();
+}
+
+#[test]
+fn test_public_claim() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{public_claim: true, ..config(&test, vec![&test.env, test.claim_address[0].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.env.auths(), []);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_private_claim_requires_beneficiary_auth() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.env.auths().len(), 1);
+    assert_eq!(test.env.auths()[0].0, test.claim_address[0]);
+}
+
+#[test]
+fn test_redistribute_unclaimed() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12347});
+
+    test.contract.redistribute();
+    assert_eq!(test.token.balance(&test.claim_address[0]), 150);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 150);
+    assert_eq!(test.token.balance(&test.claim_address[2]), 0);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_redistribute_leaves_dust() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &101, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12347});
+
+    test.contract.redistribute();
+    assert_eq!(test.token.balance(&test.claim_address[0]), 151);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 151);
+    assert_eq!(test.token.balance(&test.contract.address), 1);
+
+    assert_eq!(test.contract.sweep(), 1);
+}
+
+#[test]
+#[should_panic(expected = "time bound not expired")]
+fn test_redistribute_before_expiry_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.redistribute();
+}
+
+#[test]
+fn test_claim_event_remaining_total() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim(&test.claim_address[0]);
+    let events = test.env.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &test.env,
+            (
+                test.contract.address.clone(),
+                (symbol_short!("claim"), test.claim_address[0].clone()).into_val(&test.env),
+                (100_i128, 100_i128).into_val(&test.env),
+            )
+        ]
+    );
+
+    test.contract.claim(&test.claim_address[1]);
+    let events = test.env.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            &test.env,
+            (
+                test.contract.address.clone(),
+                (symbol_short!("claim"), test.claim_address[1].clone()).into_val(&test.env),
+                (100_i128, 0_i128).into_val(&test.env),
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_pool_drain() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &250, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(test.token.balance(&test.deposit_address), 750);
+    assert_eq!(test.token.balance(&test.contract.address), 250);
+
+    test.contract.claim_amount(&test.claim_address[0], &100);
+    test.contract.claim_amount(&test.claim_address[1], &100);
+    test.contract.claim_amount(&test.claim_address[2], &50);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+    assert_eq!(test.token.balance(&test.claim_address[2]), 50);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_pool_contributions() {
+    let test = ClaimableBalanceTest::setup();
+    let contributor = Address::generate(&test.env);
+    let other_contributor = Address::generate(&test.env);
+    test.token.transfer(&test.deposit_address, &contributor, &100);
+    test.token.transfer(&test.deposit_address, &other_contributor, &100);
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &100, &150, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12346});
+    test.contract.contribute(&contributor, &100);
+    test.contract.contribute(&other_contributor, &100);
+    assert_eq!(test.token.balance(&contributor), 0);
+    assert_eq!(test.token.balance(&other_contributor), 0);
+    assert_eq!(test.token.balance(&test.contract.address), 300);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12346});
+    test.contract.claim_amount(&test.claim_address[0], &150);
+    test.contract.claim_amount(&test.claim_address[1], &150);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 150);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 150);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "claim window open")]
+fn test_pool_contribution_after_open_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &100, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12346});
+    test.env.ledger().with_mut(|li| {li.timestamp = 12346});
+    test.contract.contribute(&test.deposit_address, &100);
+}
+
+#[test]
+fn test_pool_claim_in_installments() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &250, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim_amount(&test.claim_address[0], &40);
+    assert_eq!(test.contract.remaining_cap(&test.claim_address[0]), 60);
+    test.contract.claim_amount(&test.claim_address[0], &60);
+    assert_eq!(test.contract.remaining_cap(&test.claim_address[0]), 0);
+    assert_eq!(test.contract.remaining_cap(&test.claim_address[1]), 100);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+#[test]
+fn test_credit_claims_and_withdraw() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &250, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.set_credit_claims(&true);
+
+    test.contract.claim_amount(&test.claim_address[0], &40);
+    test.contract.claim_amount(&test.claim_address[0], &60);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 0);
+    assert_eq!(test.contract.credit(&test.claim_address[0]), 100);
+
+    assert_eq!(test.contract.withdraw(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.contract.credit(&test.claim_address[0]), 0);
+    assert_eq!(test.token.balance(&test.contract.address), 150);
+}
+
+#[test]
+#[should_panic(expected = "no credit")]
+fn test_withdraw_without_credit_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &250, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.set_credit_claims(&true);
+
+    test.contract.claim_amount(&test.claim_address[0], &100);
+    test.contract.withdraw(&test.claim_address[0]);
+    test.contract.withdraw(&test.claim_address[0]);
+}
+
+#[test]
+#[should_panic(expected = "exceeds personal cap")]
+fn test_pool_claim_over_personal_cap_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &250, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim_amount(&test.claim_address[0], &40);
+    test.contract.claim_amount(&test.claim_address[0], &60);
+    test.contract.claim_amount(&test.claim_address[0], &1);
+}
+
+#[test]
+#[should_panic(expected = "exceeds max per beneficiary")]
+fn test_pool_over_cap_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &250, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim_amount(&test.claim_address[0], &101);
+}
+
+#[test]
+#[should_panic(expected = "insufficient pool")]
+fn test_pool_exhausted_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &150, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim_amount(&test.claim_address[0], &100);
+    test.contract.claim_amount(&test.claim_address[1], &100);
+}
+
+#[test]
+fn test_contract_balance() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(test.contract.contract_balance(), 200);
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.contract.contract_balance(), 100);
+
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.contract.contract_balance(), 0);
+}
+
+#[test]
+fn test_finalize_complete_balance() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.env.as_contract(&test.contract.address, || {
+        let mut balance: ClaimableBalance =
+            test.env.storage().instance().get(&DataKey::Balance).unwrap();
+        balance.claimed_beneficiaries.push_back(test.claim_address[0].clone());
+        test.env.storage().instance().set(&DataKey::Balance, &balance);
+    });
+
+    test.contract.finalize();
+    test.env.as_contract(&test.contract.address, || {
+        assert!(!test.env.storage().instance().has(&DataKey::Balance));
+    });
+}
+
+#[test]
+#[should_panic(expected = "distribution not complete")]
+fn test_finalize_incomplete_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.finalize();
+}
+
+
+#[test]
+fn test_claim_transfer_failure_pending() {
+    let test = ClaimableBalanceTest::setup();
+    let token = FreezableTokenClient::new(&test.env, &test.env.register_contract(None, FreezableToken));
+    token.mint(&test.deposit_address, &100);
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{token: token.address.clone(), defer_failed_transfers: true, ..config(&test, vec![&test.env, test.claim_address[0].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+
+    token.set_frozen(&true);
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(token.balance(&test.claim_address[0]), 0);
+    assert_eq!(token.balance(&test.contract.address), 100);
+
+    token.set_frozen(&false);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12347});
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(token.balance(&test.claim_address[0]), 100);
+    assert_eq!(token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_deposit_from_allowance() {
+    let test = ClaimableBalanceTest::setup();
+    test.token.approve(&test.deposit_address, &test.contract.address, &200, &1000);
+
+    test.contract.deposit_from(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(test.token.balance(&test.deposit_address), 800);
+    assert_eq!(test.token.balance(&test.contract.address), 200);
+    assert_eq!(test.token.allowance(&test.deposit_address, &test.contract.address), 0);
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+#[test]
+#[should_panic]
+fn test_deposit_from_insufficient_allowance_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.token.approve(&test.deposit_address, &test.contract.address, &100, &1000);
+
+    test.contract.deposit_from(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+fn test_distribute_in_ranges() {
+    let test = ClaimableBalanceTest::setup();
+    let mut beneficiaries = vec![&test.env];
+    for _ in 0..10 {
+        beneficiaries.push_back(Address::generate(&test.env));
+    }
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &beneficiaries, &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    assert_eq!(test.contract.distribute_range(&0, &5), vec![&test.env]);
+    for beneficiary in beneficiaries.slice(0..5).iter() {
+        assert_eq!(test.token.balance(&beneficiary), 100);
+    }
+    assert_eq!(test.token.balance(&beneficiaries.get(5).unwrap()), 0);
+
+    test.contract.claim(&beneficiaries.get(7).unwrap());
+    assert_eq!(test.contract.distribute_range(&5, &10), vec![&test.env]);
+    for beneficiary in beneficiaries.iter() {
+        assert_eq!(test.token.balance(&beneficiary), 100);
+    }
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_distribute_range_skips_failed_transfer() {
+    let test = ClaimableBalanceTest::setup();
+    let token = FreezableTokenClient::new(&test.env, &test.env.register_contract(None, FreezableToken));
+    token.mint(&test.deposit_address, &300);
+    test.contract.deposit(
+        &test.deposit_address, &token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    token.freeze_account(&test.claim_address[1]);
+    assert_eq!(test.contract.distribute_range(&0, &3), vec![&test.env, test.claim_address[1].clone()]);
+    assert_eq!(token.balance(&test.claim_address[0]), 100);
+    assert_eq!(token.balance(&test.claim_address[1]), 0);
+    assert_eq!(token.balance(&test.claim_address[2]), 100);
+    assert_eq!(test.contract.beneficiary_status(&test.claim_address[1]), symbol_short!("pending"));
+}
+
+#[test]
+fn test_footprint() {
+    let test = ClaimableBalanceTest::setup();
+    assert_eq!(test.contract.footprint(), 0);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(test.contract.footprint(), 3);
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.contract.footprint(), 4);
+}
+
+#[test]
+#[should_panic(expected = "token mismatch")]
+fn test_token_swap_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    let (other_token, _) = create_token_contract(&test.env, &test.deposit_address);
+    test.env.as_contract(&test.contract.address, || {
+        let mut balance: ClaimableBalance =
+            test.env.storage().instance().get(&DataKey::Balance).unwrap();
+        balance.token = other_token.address.clone();
+        test.env.storage().instance().set(&DataKey::Balance, &balance);
+    });
+
+    test.contract.claim(&test.claim_address[0]);
+}
+
+#[test]
+fn test_contract_wallet_beneficiary_claim() {
+    let test = ClaimableBalanceTest::setup();
+    let wallet = test.env.register_contract(None, TestWallet);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, wallet.clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.env.set_auths(&[SorobanAuthorizationEntry {
+        credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+            address: ScAddress::try_from(&wallet).unwrap(),
+            nonce: 0,
+            signature_expiration_ledger: 100,
+            signature: ScVal::Void,
+        }),
+        root_invocation: SorobanAuthorizedInvocation {
+            function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+                contract_address: ScAddress::try_from(&test.contract.address).unwrap(),
+                function_name: "claim".try_into().unwrap(),
+                args: std::vec![ScVal::try_from(&wallet).unwrap()].try_into().unwrap(),
+            }),
+            sub_invocations: VecM::default(),
+        },
+    }]);
+
+    test.contract.claim(&wallet);
+    assert_eq!(test.token.balance(&wallet), 100);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_reconstruct_state_from_events() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.claim(&test.claim_address[2]);
+    test.contract.claim(&test.claim_address[0]);
+
+    let events = test.env.events().all();
+
+    let mut total_amount = 0_i128;
+    let mut claimed_beneficiaries = vec![&test.env];
+    for (contract, topics, data) in events.iter() {
+        if contract != test.contract.address {
+            continue;
+        }
+        let kind: Symbol = topics.get(0).unwrap().into_val(&test.env);
+        if kind == symbol_short!("deposit") {
+            let (_, amount, beneficiaries): (Address, i128, soroban_sdk::Vec<Address>) =
+                data.into_val(&test.env);
+            total_amount = amount * beneficiaries.len() as i128;
+        } else if kind == symbol_short!("claim") {
+            let beneficiary: Address = topics.get(1).unwrap().into_val(&test.env);
+            let (amount, remaining_total): (i128, i128) = data.into_val(&test.env);
+            total_amount -= amount;
+            assert_eq!(total_amount, remaining_total);
+            claimed_beneficiaries.push_back(beneficiary);
+        }
+    }
+
+    let balance = test.contract.get_balance();
+    assert_eq!(total_amount, balance.total_amount);
+    assert_eq!(claimed_beneficiaries, balance.claimed_beneficiaries);
+}
+
+#[test]
+#[should_panic(expected = "timebound already expired")]
+fn test_expired_before_deposit_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12345});
+}
+
+#[test]
+fn test_time_bound_kind_before() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(test.contract.time_bound_kind(), symbol_short!("before"));
+}
+
+#[test]
+fn test_time_bound_kind_after() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &100, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12346});
+    assert_eq!(test.contract.time_bound_kind(), symbol_short!("after"));
+}
+
+#[test]
+fn test_claim_without_time_bound() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::None, timestamp: 0});
+    assert_eq!(test.contract.time_bound_kind(), symbol_short!("none"));
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+fn three_tranches(env: &Env) -> soroban_sdk::Vec<Tranche> {
+    vec![
+        env,
+        Tranche { unlock_timestamp: 12400, fraction_bps: 2500, claim_deadline: None },
+        Tranche { unlock_timestamp: 12500, fraction_bps: 2500, claim_deadline: None },
+        Tranche { unlock_timestamp: 12600, fraction_bps: 5000, claim_deadline: None },
+    ]
+}
+
+#[test]
+fn test_released_by_tranches_large_amount() {
+    let env = Env::default();
+    let tranches = three_tranches(&env);
+    assert_eq!(released_by_tranches(100, &tranches, 1), 25);
+    assert_eq!(released_by_tranches(i128::MAX, &tranches, 1), i128::MAX / 4);
+    assert_eq!(released_by_tranches(i128::MAX, &tranches, 3), i128::MAX);
+}
+
+#[test]
+fn test_claim_tranches_incrementally() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_schedule(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &three_tranches(&test.env));
+    assert_eq!(test.token.balance(&test.contract.address), 200);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12400});
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[0]), 25);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12550});
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[0]), 25);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12600});
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[0]), 50);
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[1]), 100);
+
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_claim_tranches_forfeits_expired() {
+    let test = ClaimableBalanceTest::setup();
+    let tranches = vec![
+        &test.env,
+        Tranche { unlock_timestamp: 12400, fraction_bps: 2500, claim_deadline: Some(12450) },
+        Tranche { unlock_timestamp: 12500, fraction_bps: 7500, claim_deadline: Some(12600) },
+    ];
+    test.contract.deposit_schedule(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &tranches);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12550});
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[0]), 75);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 75);
+
+    assert_eq!(test.contract.sweep(), 25);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_claim_expired_tranches_not_logged() {
+    let test = ClaimableBalanceTest::setup();
+    let tranches = vec![
+        &test.env,
+        Tranche { unlock_timestamp: 12400, fraction_bps: 2500, claim_deadline: Some(12450) },
+        Tranche { unlock_timestamp: 12500, fraction_bps: 7500, claim_deadline: Some(12600) },
+    ];
+    test.contract.deposit_schedule(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &tranches);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12460});
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[0]), 0);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12500});
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[0]), 75);
+
+    assert_eq!(test.contract.claim_at(&0), (test.claim_address[0].clone(), 12500));
+}
+
+#[test]
+fn test_sweep_expired_tranches_never_claimed() {
+    let test = ClaimableBalanceTest::setup();
+    let tranches = vec![
+        &test.env,
+        Tranche { unlock_timestamp: 12400, fraction_bps: 2500, claim_deadline: Some(12450) },
+        Tranche { unlock_timestamp: 12500, fraction_bps: 7500, claim_deadline: Some(12600) },
+    ];
+    test.contract.deposit_schedule(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &tranches);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12550});
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[0]), 75);
+    assert_eq!(test.contract.sweep(), 50);
+    assert_eq!(test.token.balance(&test.contract.address), 75);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12700});
+    assert_eq!(test.contract.sweep(), 75);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 0);
+}
+
+#[test]
+#[should_panic(expected = "nothing to claim")]
+fn test_claim_locked_tranche_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_schedule(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &three_tranches(&test.env));
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12450});
+    test.contract.claim_tranches(&test.claim_address[0]);
+    test.contract.claim_tranches(&test.claim_address[0]);
+}
+
+#[test]
+#[should_panic(expected = "invalid schedule")]
+fn test_schedule_fraction_sum_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_schedule(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &vec![&test.env, Tranche { unlock_timestamp: 12400, fraction_bps: 5000, claim_deadline: None }]);
+}
+
+#[test]
+fn test_claim_extends_ttl() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    let live_until = instance_live_until(&test.env, &test.contract.address);
+
+    test.env.ledger().with_mut(|li| {li.sequence_number = live_until - 10});
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(
+        instance_live_until(&test.env, &test.contract.address),
+        live_until - 10 + INSTANCE_BUMP_AMOUNT
+    );
+}
+
+#[test]
+fn test_extend_ttl_at_cap() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.extend_ttl(&MAX_TTL_EXTENSION);
+    assert_eq!(
+        instance_live_until(&test.env, &test.contract.address),
+        test.env.ledger().sequence() + MAX_TTL_EXTENSION
+    );
+}
+
+#[test]
+#[should_panic(expected = "extension too large")]
+fn test_extend_ttl_over_cap_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.extend_ttl(&(MAX_TTL_EXTENSION + 1));
+}
+
+#[test]
+fn test_claim_log() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim(&test.claim_address[1]);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12346});
+    test.contract.claim(&test.claim_address[0]);
+
+    assert_eq!(test.contract.claim_at(&0), (test.claim_address[1].clone(), 12345));
+    assert_eq!(test.contract.claim_at(&1), (test.claim_address[0].clone(), 12346));
+}
+
+#[test]
+fn test_claim_log_many_installments() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &100, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    for _ in 0..50 {
+        test.contract.claim_amount(&test.claim_address[0], &1);
+    }
+    assert_eq!(test.contract.claim_at(&49), (test.claim_address[0].clone(), 12345));
+    assert_eq!(test.contract.claimed_amount_for(&test.claim_address[0]), 50);
+}
+
+#[test]
+#[should_panic(expected = "claim index out of range")]
+fn test_claim_log_out_of_range_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim(&test.claim_address[1]);
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim_at(&2);
+}
+
+#[test]
+fn test_deposit_pot_even() {
+    let test = ClaimableBalanceTest::setup();
+    let change = test.contract.deposit_pot(
+        &test.deposit_address, &test.token.address, &300, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(change, 0);
+    assert_eq!(test.token.balance(&test.deposit_address), 700);
+    assert_eq!(test.token.balance(&test.contract.address), 300);
+    assert_eq!(test.contract.get_balance().amount_per_beneficiary, 100);
+}
+
+#[test]
+fn test_deposit_pot_with_change() {
+    let test = ClaimableBalanceTest::setup();
+    let change = test.contract.deposit_pot(
+        &test.deposit_address, &test.token.address, &302, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(change, 2);
+    assert_eq!(test.token.balance(&test.deposit_address), 700);
+    assert_eq!(test.token.balance(&test.contract.address), 300);
+    assert_eq!(test.contract.get_balance().amount_per_beneficiary, 100);
+}
+
+#[test]
+fn test_validate_deposit() {
+    let test = ClaimableBalanceTest::setup();
+    let beneficiaries = vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()];
+    let open = TimeBound{kind: TimeBoundKind::Before, timestamp: 12346};
+    assert!(test.contract.validate_deposit(&100, &beneficiaries, &open));
+
+    assert!(!test.contract.validate_deposit(&-1, &beneficiaries, &open));
+    let mut too_many = vec![&test.env];
+    for _ in 0..11 {
+        too_many.push_back(Address::generate(&test.env));
+    }
+    assert!(!test.contract.validate_deposit(&100, &too_many, &open));
+    assert!(!test.contract.validate_deposit(&100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[0].clone()], &open));
+    assert!(!test.contract.validate_deposit(&100, &beneficiaries, &TimeBound{kind: TimeBoundKind::Before, timestamp: 12345}));
+
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &beneficiaries, &open);
+    assert!(!test.contract.validate_deposit(&100, &beneficiaries, &open));
+}
+
+#[test]
+#[should_panic(expected = "duplicate beneficiary")]
+fn test_deposit_duplicate_beneficiary_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+#[should_panic(expected = "duplicate beneficiary")]
+fn test_deposit_schedule_duplicate_beneficiary_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_schedule(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[0].clone()], &vec![&test.env, Tranche { unlock_timestamp: 12400, fraction_bps: 10000, claim_deadline: None }]);
+}
+
+#[test]
+#[should_panic(expected = "duplicate beneficiary")]
+fn test_deposit_stream_duplicate_beneficiary_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_stream(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[0].clone()], &2, &12345);
+}
+
+#[test]
+#[should_panic(expected = "duplicate beneficiary")]
+fn test_deposit_and_distribute_duplicate_beneficiary_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_and_distribute(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[0].clone()]);
+}
+
+#[test]
+fn test_deposit_config() {
+    let test = ClaimableBalanceTest::setup();
+    let description = String::from_str(&test.env, "Q1 2025 Grants");
+    test.contract.deposit_config(
+        &test.deposit_address,
+        &DepositConfig {
+            token: test.token.address.clone(),
+            amount_per_beneficiary: 100,
+            beneficiaries: vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()],
+            timebound: TimeBound{kind: TimeBoundKind::Before, timestamp: 12346},
+            public_claim: true,
+            defer_failed_transfers: false,
+            ordered: true,
+            requires_release: false,
+            max_total: Some(300),
+            co_sign: false,
+            auto_refund: true,
+            description: description.clone(),
+            max_fee_bps: None,
+            irrevocable: false,
+        },
+    );
+    assert_eq!(test.contract.get_description(), description);
+    let balance = test.contract.get_balance();
+    assert!(balance.public_claim);
+    assert!(balance.ordered);
+
+    test.token.transfer(&test.deposit_address, &test.contract.address, &50);
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+    assert_eq!(test.token.balance(&test.deposit_address), 800);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_deposit_under_max_total() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{max_total: Some(200), ..config(&test, vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+
+    assert_eq!(test.token.balance(&test.contract.address), 200);
+}
+
+#[test]
+#[should_panic(expected = "exceeds max total")]
+fn test_deposit_over_max_total_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{max_total: Some(199), ..config(&test, vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+}
+
+#[test]
+fn test_renounce_and_reclaim() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.renounce(&test.claim_address[1]);
+    assert_eq!(test.contract.get_balance().total_amount, 200);
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[2]);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 0);
+
+    assert_eq!(test.contract.sweep(), 100);
+    assert_eq!(test.token.balance(&test.deposit_address), 800);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_total_beneficiaries_ever() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+
+    assert_eq!(test.contract.footprint(), 0);
+    assert_eq!(test.contract.total_beneficiaries_ever(), 2);
+}
+
+#[test]
+fn test_deposit_and_claim_max_amount() {
+    let test = ClaimableBalanceTest::setup();
+    let (token, token_admin_client) = create_token_contract(&test.env, &Address::generate(&test.env));
+    token_admin_client.mint(&test.deposit_address, &i128::MAX);
+    test.contract.deposit(
+        &test.deposit_address, &token.address, &i128::MAX, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    assert_eq!(test.contract.get_balance().total_amount, i128::MAX);
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(token.balance(&test.claim_address[0]), i128::MAX);
+    assert_eq!(token.balance(&test.contract.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "amount overflow")]
+fn test_deposit_total_overflow_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &i128::MAX, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+fn test_deposit_at_max_amount() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_max_amount(&Some(100));
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    assert_eq!(test.token.balance(&test.contract.address), 200);
+}
+
+#[test]
+#[should_panic(expected = "exceeds max amount")]
+fn test_deposit_over_max_amount_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_max_amount(&Some(100));
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &101, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+#[should_panic(expected = "beneficiary blacklisted")]
+fn test_deposit_blacklisted_beneficiary_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.blacklist_add(&test.claim_address[1]);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+#[should_panic(expected = "beneficiary blacklisted")]
+fn test_claim_blacklisted_after_deposit_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.blacklist_add(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[0]);
+}
+
+#[test]
+fn test_blacklist_remove() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.blacklist_add(&test.claim_address[0]);
+    test.contract.blacklist_remove(&test.claim_address[0]);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+#[test]
+fn test_blacklisted_beneficiary_status() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.blacklist_add(&test.claim_address[0]);
+    assert_eq!(test.contract.beneficiary_status(&test.claim_address[0]), symbol_short!("blocked"));
+    assert!(!test.contract.is_claimable(&test.claim_address[0]));
+    assert!(test.contract.is_claimable(&test.claim_address[1]));
+}
+
+#[test]
+fn test_distribute_range_ordered_after_failed_transfer() {
+    let test = ClaimableBalanceTest::setup();
+    let token = FreezableTokenClient::new(&test.env, &test.env.register_contract(None, FreezableToken));
+    token.mint(&test.deposit_address, &300);
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{token: token.address.clone(), ordered: true, ..config(&test, vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+
+    token.freeze_account(&test.claim_address[0]);
+    assert_eq!(test.contract.distribute_range(&0, &3), vec![&test.env, test.claim_address[0].clone()]);
+    assert_eq!(token.balance(&test.claim_address[0]), 0);
+    assert_eq!(token.balance(&test.claim_address[1]), 100);
+    assert_eq!(token.balance(&test.claim_address[2]), 100);
+}
+
+#[test]
+fn test_distribute_range_ordered_skips_blacklisted() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{ordered: true, ..config(&test, vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+    test.contract.blacklist_add(&test.claim_address[0]);
+    assert_eq!(test.contract.distribute_range(&0, &2), vec![&test.env]);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+}
+
+#[test]
+fn test_distribute_range_skips_blacklisted() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.blacklist_add(&test.claim_address[1]);
+    assert_eq!(test.contract.distribute_range(&0, &3), vec![&test.env]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 0);
+    assert_eq!(test.token.balance(&test.claim_address[2]), 100);
+    assert_eq!(test.token.balance(&test.contract.address), 100);
+}
+
+#[test]
+fn test_redistribute_credited() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+    test.contract.set_credit_claims(&true);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12347});
+
+    test.contract.redistribute();
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.contract.credit(&test.claim_address[0]), 50);
+    assert_eq!(test.contract.credit(&test.claim_address[1]), 50);
+    assert_eq!(test.contract.claimed_amount_for(&test.claim_address[0]), 150);
+    assert_eq!(test.contract.withdraw(&test.claim_address[1]), 50);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 150);
+}
+
+#[test]
+fn test_redistribute_skips_renounced() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.renounce(&test.claim_address[1]);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12347});
+
+    test.contract.redistribute();
+    assert_eq!(test.token.balance(&test.claim_address[0]), 200);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 0);
+    assert_eq!(test.token.balance(&test.contract.address), 100);
+    assert_eq!(test.contract.sweep(), 100);
+}
+
+#[test]
+fn test_redistribute_skips_blacklisted() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+    test.contract.blacklist_add(&test.claim_address[1]);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12347});
+
+    test.contract.redistribute();
+    assert_eq!(test.token.balance(&test.claim_address[0]), 200);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "invalid self-transfer")]
+fn test_claim_self_transfer_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{public_claim: true, ..config(&test, vec![&test.env, test.contract.address.clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+    test.contract.claim(&test.contract.address);
+}
+
+#[test]
+fn test_deposit_fee() {
+    let test = ClaimableBalanceTest::setup();
+    let treasury = Address::generate(&test.env);
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_deposit_fee(&100, &treasury);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    assert_eq!(test.token.balance(&test.deposit_address), 800);
+    assert_eq!(test.token.balance(&treasury), 2);
+    assert_eq!(test.token.balance(&test.contract.address), 198);
+    let balance = test.contract.get_balance();
+    assert_eq!(balance.amount_per_beneficiary, 99);
+    assert_eq!(balance.total_amount, 198);
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 99);
+}
+
+#[test]
+fn test_deposit_fee_on_pool() {
+    let test = ClaimableBalanceTest::setup();
+    let treasury = Address::generate(&test.env);
+    let contributor = Address::generate(&test.env);
+    test.token.transfer(&test.deposit_address, &contributor, &100);
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_deposit_fee(&100, &treasury);
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &300, &200, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12346});
+
+    assert_eq!(test.token.balance(&test.deposit_address), 600);
+    assert_eq!(test.token.balance(&treasury), 3);
+    assert_eq!(test.token.balance(&test.contract.address), 297);
+
+    test.contract.contribute(&contributor, &100);
+    assert_eq!(test.token.balance(&contributor), 0);
+    assert_eq!(test.token.balance(&treasury), 4);
+    assert_eq!(test.token.balance(&test.contract.address), 396);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12346});
+    test.contract.claim_amount(&test.claim_address[0], &200);
+    test.contract.claim_amount(&test.claim_address[1], &196);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_deposit_fee_on_schedule() {
+    let test = ClaimableBalanceTest::setup();
+    let treasury = Address::generate(&test.env);
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_deposit_fee(&100, &treasury);
+    test.contract.deposit_schedule(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &three_tranches(&test.env));
+
+    assert_eq!(test.token.balance(&test.deposit_address), 800);
+    assert_eq!(test.token.balance(&treasury), 2);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12600});
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[0]), 99);
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[1]), 99);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_deposit_fee_on_stream() {
+    let test = ClaimableBalanceTest::setup();
+    let treasury = Address::generate(&test.env);
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_deposit_fee(&100, &treasury);
+    test.contract.deposit_stream(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &2, &12345);
+
+    assert_eq!(test.token.balance(&test.deposit_address), 800);
+    assert_eq!(test.token.balance(&treasury), 2);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12345 + 100});
+    assert_eq!(test.contract.claim_stream(&test.claim_address[0]), 99);
+    assert_eq!(test.contract.claim_stream(&test.claim_address[1]), 99);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "fee above limit")]
+fn test_deposit_fee_above_depositor_limit_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_deposit_fee(&100, &Address::generate(&test.env));
+    test.contract.deposit_config(
+        &test.deposit_address,
+        &DepositConfig {
+            max_fee_bps: Some(50),
+            ..default_config(&test.env, test.token.address.clone(), 100, vec![&test.env, test.claim_address[0].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid fee")]
+fn test_set_deposit_fee_above_cap_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.set_deposit_fee(&(MAX_FEE_BPS + 1), &Address::generate(&test.env));
+}
+
+#[test]
+fn test_deposit_fee_not_retroactive() {
+    let test = ClaimableBalanceTest::setup();
+    let treasury = Address::generate(&test.env);
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.set_deposit_fee(&100, &treasury);
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&treasury), 0);
+}
+
+#[test]
+#[should_panic(expected = "exceeds max amount")]
+fn test_deposit_pool_cap_over_max_amount_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_max_amount(&Some(100));
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &100, &101, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+#[should_panic(expected = "exceeds max amount")]
+fn test_deposit_pool_total_over_max_amount_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_max_amount(&Some(100));
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &201, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+fn test_deposit_pool_within_max_amount() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_max_amount(&Some(100));
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &200, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(test.token.balance(&test.contract.address), 200);
+}
+
+#[test]
+fn test_ordered_claims() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{ordered: true, ..config(&test, vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+}
+
+#[test]
+#[should_panic(expected = "out of order claim")]
+fn test_out_of_order_claim_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{ordered: true, ..config(&test, vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+
+    test.contract.claim(&test.claim_address[1]);
+}
+
+#[test]
+fn test_ordered_claim_skips_blacklisted() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{ordered: true, ..config(&test, vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+    test.contract.blacklist_add(&test.claim_address[0]);
+
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+}
+
+#[test]
+fn test_deposit_time() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12346});
+    assert_eq!(test.contract.deposit_time(), 12345);
+}
+
+#[test]
+#[should_panic(expected = "not initialized")]
+fn test_claim_before_deposit_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.claim(&test.claim_address[0]);
+}
+
+#[test]
+#[should_panic(expected = "distribution complete")]
+fn test_claim_after_distribution_complete_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.claim(&test.claim_address[0]);
+
+    test.contract.claim(&test.claim_address[0]);
+}
+
+#[test]
+fn test_get_depositor() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    assert_eq!(test.contract.get_depositor(), test.deposit_address);
+}
+
+#[test]
+#[should_panic(expected = "not released")]
+fn test_claim_time_passed_not_released_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{requires_release: true, ..config(&test, vec![&test.env, test.claim_address[0].clone()], TimeBound{kind: TimeBoundKind::After, timestamp: 12346})});
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12346});
+    test.contract.claim(&test.claim_address[0]);
+}
+
+#[test]
+#[should_panic(expected = "claim not yet open")]
+fn test_claim_released_time_not_passed_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{requires_release: true, ..config(&test, vec![&test.env, test.claim_address[0].clone()], TimeBound{kind: TimeBoundKind::After, timestamp: 12346})});
+
+    test.contract.set_released(&true);
+    test.contract.claim(&test.claim_address[0]);
+}
+
+#[test]
+fn test_claim_released_and_time_passed() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{requires_release: true, ..config(&test, vec![&test.env, test.claim_address[0].clone()], TimeBound{kind: TimeBoundKind::After, timestamp: 12346})});
+
+    test.contract.set_released(&true);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12346});
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+#[test]
+fn test_claimed_amount_for_tranches() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_schedule(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &three_tranches(&test.env));
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12400});
+    test.contract.claim_tranches(&test.claim_address[0]);
+    assert_eq!(test.contract.claimed_amount_for(&test.claim_address[0]), 25);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12500});
+    test.contract.claim_tranches(&test.claim_address[0]);
+    assert_eq!(test.contract.claimed_amount_for(&test.claim_address[0]), 50);
+    assert_eq!(test.contract.claimed_amount_for(&test.claim_address[1]), 0);
+}
+
+#[test]
+fn test_auto_refund_on_final_claim() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.set_auto_refund(&true);
+    test.token.transfer(&test.deposit_address, &test.contract.address, &50);
+    assert_eq!(test.token.balance(&test.deposit_address), 750);
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.deposit_address), 750);
+    test.contract.claim(&test.claim_address[1]);
+
+    assert_eq!(test.token.balance(&test.deposit_address), 800);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_final_claim_refunds_stray_tokens() {
+    let test = ClaimableBalanceTest::setup();
+    let stranger = Address::generate(&test.env);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&stranger, &1);
+    test.token.transfer(&stranger, &test.contract.address, &1);
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+    assert_eq!(test.token.balance(&test.deposit_address), 801);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_final_claim_after_sweeping_renounced_share() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.renounce(&test.claim_address[0]);
+    assert_eq!(test.contract.sweep(), 100);
+
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+    assert_eq!(test.token.balance(&test.deposit_address), 900);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_final_claim_zeroes_balance() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.set_auto_refund(&true);
+    test.contract.renounce(&test.claim_address[2]);
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.token.balance(&test.deposit_address), 800);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(test.contract.remaining_total(), 0);
+}
+
+#[test]
+fn test_beneficiary_status() {
+    let test = ClaimableBalanceTest::setup();
+    let outsider = Address::generate(&test.env);
+    assert_eq!(test.contract.beneficiary_status(&test.claim_address[0]), symbol_short!("none"));
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{requires_release: true, ..config(&test, vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], TimeBound{kind: TimeBoundKind::After, timestamp: 12346})});
+
+    assert_eq!(test.contract.beneficiary_status(&outsider), symbol_short!("none"));
+    assert_eq!(test.contract.beneficiary_status(&test.claim_address[0]), symbol_short!("locked"));
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12346});
+    assert_eq!(test.contract.beneficiary_status(&test.claim_address[0]), symbol_short!("frozen"));
+
+    test.contract.set_released(&true);
+    assert_eq!(test.contract.beneficiary_status(&test.claim_address[0]), symbol_short!("claimable"));
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.contract.beneficiary_status(&test.claim_address[0]), symbol_short!("claimed"));
+
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.contract.beneficiary_status(&test.claim_address[1]), symbol_short!("claimed"));
+}
+
+#[test]
+fn test_beneficiary_status_pending() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{defer_failed_transfers: true, ..config(&test, vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+
+    test.env.as_contract(&test.contract.address, || {
+        let mut claimable_balance: ClaimableBalance =
+            test.env.storage().instance().get(&DataKey::Balance).unwrap();
+        claimable_balance.pending_beneficiaries.push_back(test.claim_address[0].clone());
+        test.env.storage().instance().set(&DataKey::Balance, &claimable_balance);
+    });
+    assert_eq!(test.contract.beneficiary_status(&test.claim_address[0]), symbol_short!("pending"));
+}
+
+#[test]
+fn test_claim_stream() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_stream(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &2, &12345);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12355});
+    assert_eq!(test.contract.claim_stream(&test.claim_address[0]), 20);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12385});
+    assert_eq!(test.contract.claim_stream(&test.claim_address[0]), 60);
+    assert_eq!(test.contract.claim_stream(&test.claim_address[1]), 80);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 80);
+}
+
+#[test]
+fn test_claim_stream_after_min_interval() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_stream(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &2, &12345);
+    test.contract.set_min_claim_interval(&Some(10));
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12355});
+    assert_eq!(test.contract.claim_stream(&test.claim_address[0]), 20);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12365; li.sequence_number += 10});
+    assert_eq!(test.contract.claim_stream(&test.claim_address[0]), 20);
+}
+
+#[test]
+#[should_panic(expected = "claim too frequent")]
+fn test_claim_stream_within_min_interval_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_stream(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &2, &12345);
+    test.contract.set_min_claim_interval(&Some(10));
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12355});
+    test.contract.claim_stream(&test.claim_address[0]);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12365; li.sequence_number += 9});
+    test.contract.claim_stream(&test.claim_address[0]);
+}
+
+#[test]
+fn test_claim_stream_capped_at_allocation() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_stream(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &2, &12345);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12345 + 3600});
+    assert_eq!(test.contract.claim_stream(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+const YEAR: u64 = 365 * 24 * 60 * 60;
+
+#[test]
+fn test_quarterly_schedule_over_a_year() {
+    let test = ClaimableBalanceTest::setup();
+    let (token, token_admin_client) = create_token_contract(&test.env, &Address::generate(&test.env));
+    token_admin_client.mint(&test.deposit_address, &1_000_000_007);
+    let start = 12345;
+    let mut tranches = vec![&test.env];
+    for quarter in 1..=4 {
+        tranches.push_back(Tranche { unlock_timestamp: start + quarter * YEAR / 4, fraction_bps: 2500, claim_deadline: None });
+    }
+    test.contract.deposit_schedule(
+        &test.deposit_address, &token.address, &1_000_000_007, &vec![&test.env, test.claim_address[0].clone()], &tranches);
+    assert_eq!(test.contract.vesting_complete_at(), start + YEAR);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = start + YEAR / 4 - 1});
+    assert_eq!(test.contract.vesting_progress(), 0);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = start + YEAR / 2});
+    assert_eq!(test.contract.vesting_progress(), 5000);
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[0]), 500_000_003);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = start + YEAR});
+    assert_eq!(test.contract.vesting_progress(), 10_000);
+    assert_eq!(test.contract.claim_tranches(&test.claim_address[0]), 500_000_004);
+    assert_eq!(token.balance(&test.claim_address[0]), 1_000_000_007);
+}
+
+#[test]
+fn test_claim_weighted() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_weighted(
+        &test.deposit_address, &test.token.address, &999, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &vec![&test.env, 5000, 3000, 2000], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    assert_eq!(test.contract.claim_weighted(&test.claim_address[0]), 499);
+    assert_eq!(test.contract.claim_weighted(&test.claim_address[1]), 300);
+    assert_eq!(test.contract.claim_weighted(&test.claim_address[2]), 200);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(test.token.balance(&test.deposit_address), 1);
+}
+
+#[test]
+fn test_weighted_remainder_to_first_on_ties() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_weighted(
+        &test.deposit_address, &test.token.address, &998, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &vec![&test.env, 3000, 3000, 4000], &TimeBound{kind: TimeBoundKind::None, timestamp: 0});
+
+    assert_eq!(test.contract.claim_weighted(&test.claim_address[0]), 300);
+    assert_eq!(test.contract.claim_weighted(&test.claim_address[1]), 299);
+    assert_eq!(test.contract.claim_weighted(&test.claim_address[2]), 399);
+}
+
+#[test]
+#[should_panic(expected = "invalid weights")]
+fn test_deposit_weighted_invalid_weights_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_weighted(
+        &test.deposit_address, &test.token.address, &999, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &vec![&test.env, 5000, 3000], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+fn test_stream_over_a_year() {
+    let test = ClaimableBalanceTest::setup();
+    let (token, token_admin_client) = create_token_contract(&test.env, &Address::generate(&test.env));
+    let rate = 3;
+    let allocation = rate * YEAR as i128;
+    token_admin_client.mint(&test.deposit_address, &allocation);
+    let start = 12345;
+    test.contract.deposit_stream(
+        &test.deposit_address, &token.address, &allocation, &vec![&test.env, test.claim_address[0].clone()], &rate, &start);
+
+    let mut claimed = 0;
+    for day in [1, 90, 182, 300] {
+        test.env.ledger().with_mut(|li| {li.timestamp = start + day * 24 * 60 * 60});
+        claimed += test.contract.claim_stream(&test.claim_address[0]);
+        assert_eq!(claimed, rate * (day * 24 * 60 * 60) as i128);
+    }
+
+    test.env.ledger().with_mut(|li| {li.timestamp = start + 2 * YEAR});
+    claimed += test.contract.claim_stream(&test.claim_address[0]);
+    assert_eq!(claimed, allocation);
+    assert_eq!(token.balance(&test.claim_address[0]), allocation);
+}
+
+#[test]
+fn test_vesting_progress() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_schedule(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &three_tranches(&test.env));
+
+    assert_eq!(test.contract.vesting_complete_at(), 12600);
+    assert_eq!(test.contract.vesting_progress(), 0);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12500});
+    assert_eq!(test.contract.vesting_progress(), 5000);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 13000});
+    assert_eq!(test.contract.vesting_progress(), 10000);
+}
+
+#[test]
+fn test_claim_returns_remaining_count() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    assert_eq!(test.contract.claim(&test.claim_address[2]), 2);
+    assert_eq!(test.contract.claim(&test.claim_address[0]), 1);
+    assert_eq!(test.contract.claim(&test.claim_address[1]), 0);
+}
+
+#[test]
+fn test_init_admin() {
+    let test = ClaimableBalanceTest::setup();
+    let admin = Address::generate(&test.env);
+    test.contract.init_admin(&admin);
+    assert_eq!(test.contract.get_admin(), admin);
+
+    let new_admin = Address::generate(&test.env);
+    test.contract.set_admin(&new_admin);
+    assert_eq!(
+        test.env.auths(),
+        [(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    test.contract.address.clone(),
+                    Symbol::new(&test.env, "set_admin"),
+                    (new_admin.clone(),).into_val(&test.env),
+                )),
+                sub_invocations: std::vec![]
+            }
+        ),]
+    );
+    assert_eq!(test.contract.get_admin(), new_admin);
+}
+
+#[test]
+fn test_init_admin_after_deposit_requires_depositor() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    let admin = Address::generate(&test.env);
+    test.contract.init_admin(&admin);
+    let signers: std::vec::Vec<Address> = test.env.auths().into_iter().map(|(address, _)| address).collect();
+    assert_eq!(signers, std::vec![admin, test.deposit_address.clone()]);
+}
+
+#[test]
+#[should_panic]
+fn test_init_admin_by_stranger_after_deposit_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    let stranger = Address::generate(&test.env);
+    test.contract
+        .mock_auths(&[MockAuth {
+            address: &stranger,
+            invoke: &MockAuthInvoke {
+                contract: &test.contract.address,
+                fn_name: "init_admin",
+                args: (stranger.clone(),).into_val(&test.env),
+                sub_invokes: &[],
+            },
+        }])
+        .init_admin(&stranger);
+}
+
+#[test]
+#[should_panic(expected = "admin not approved")]
+fn test_deposit_with_unapproved_admin_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+#[should_panic(expected = "admin not approved")]
+fn test_deposit_after_admin_replaced_fail() {
+    let test = ClaimableBalanceTest::setup();
+    let admin = Address::generate(&test.env);
+    test.contract.init_admin(&admin);
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.set_admin(&Address::generate(&test.env));
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+fn test_deposit_with_approved_admin() {
+    let test = ClaimableBalanceTest::setup();
+    let admin = Address::generate(&test.env);
+    test.contract.init_admin(&admin);
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(test.contract.get_admin(), admin);
+    assert_eq!(test.token.balance(&test.contract.address), 100);
+}
+
+#[test]
+#[should_panic(expected = "admin already set")]
+fn test_init_admin_twice_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.init_admin(&Address::generate(&test.env));
+}
+
+#[test]
+#[should_panic(expected = "admin not set")]
+fn test_admin_action_before_init_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.set_admin(&Address::generate(&test.env));
+}
+
+#[test]
+fn test_beneficiaries_page() {
+    let test = ClaimableBalanceTest::setup();
+    let mut beneficiaries = vec![&test.env];
+    for _ in 0..10 {
+        beneficiaries.push_back(Address::generate(&test.env));
+    }
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &10, &beneficiaries, &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    let mut pages = vec![&test.env];
+    let mut start = 0;
+    while start < beneficiaries.len() {
+        let page = test.contract.beneficiaries_page(&start, &4);
+        assert!(page.len() <= 4);
+        pages.append(&page);
+        start += 4;
+    }
+    assert_eq!(pages, beneficiaries);
+    assert_eq!(test.contract.beneficiaries_page(&8, &4).len(), 2);
+    assert_eq!(test.contract.beneficiaries_page(&12, &4).len(), 0);
+}
+
+#[test]
+fn test_beneficiaries_remaining_keeps_deposit_order() {
+    let test = ClaimableBalanceTest::setup();
+    let mut beneficiaries = vec![&test.env];
+    for _ in 0..6 {
+        beneficiaries.push_back(Address::generate(&test.env));
+    }
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &10, &beneficiaries, &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(test.contract.beneficiaries_remaining(), beneficiaries);
+
+    test.contract.claim(&beneficiaries.get(4).unwrap());
+    test.contract.claim(&beneficiaries.get(0).unwrap());
+    test.contract.claim(&beneficiaries.get(2).unwrap());
+    assert_eq!(
+        test.contract.beneficiaries_remaining(),
+        vec![&test.env, beneficiaries.get(1).unwrap(), beneficiaries.get(3).unwrap(), beneficiaries.get(5).unwrap()]
+    );
+}
+
+#[test]
+fn test_page_details() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.claim(&test.claim_address[1]);
+
+    assert_eq!(
+        test.contract.page_details(&0, &2),
+        vec![
+            &test.env,
+            (test.claim_address[0].clone(), symbol_short!("claimable"), 100_i128),
+            (test.claim_address[1].clone(), symbol_short!("claimed"), 0_i128),
+        ]
+    );
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12347});
+    assert_eq!(
+        test.contract.page_details(&2, &2),
+        vec![&test.env, (test.claim_address[2].clone(), symbol_short!("locked"), 100_i128)]
+    );
+}
+
+#[test]
+fn test_rescue_unrelated_token() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    let admin = Address::generate(&test.env);
+    test.contract.init_admin(&admin);
+
+    let (stray_token, stray_admin_client) = create_token_contract(&test.env, &Address::generate(&test.env));
+    stray_admin_client.mint(&test.contract.address, &70);
+    let recipient = Address::generate(&test.env);
+
+    assert_eq!(test.contract.rescue_token(&stray_token.address, &recipient), 70);
+    assert_eq!(stray_token.balance(&recipient), 70);
+    assert_eq!(stray_token.balance(&test.contract.address), 0);
+    assert_eq!(test.token.balance(&test.contract.address), 100);
+}
+
+#[test]
+#[should_panic(expected = "cannot rescue distribution token")]
+fn test_rescue_distribution_token_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.init_admin(&Address::generate(&test.env));
+
+    test.contract.rescue_token(&test.token.address, &Address::generate(&test.env));
+}
+
+#[test]
+#[should_panic(expected = "claim window closed")]
+fn test_claim_window_closed_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12347});
+    test.contract.claim(&test.claim_address[0]);
+}
+
+#[test]
+fn test_deposit_and_distribute() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_and_distribute(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()]);
+
+    for claim_address in test.claim_address.iter() {
+        assert_eq!(test.token.balance(claim_address), 100);
+    }
+    assert_eq!(test.token.balance(&test.deposit_address), 700);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    test.env.as_contract(&test.contract.address, || {
+        assert!(!test.env.storage().instance().has(&DataKey::Balance));
+    });
+}
+
+#[test]
+fn test_revoke_all_after_partial_claims() {
+    let test = ClaimableBalanceTest::setup();
+    let admin = Address::generate(&test.env);
+    let compliance = Address::generate(&test.env);
+    test.contract.init_admin(&admin);
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.claim(&test.claim_address[0]);
+
+    test.contract.revoke_all(&compliance);
+    assert_eq!(test.token.balance(&compliance), 200);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+    assert_eq!(test.contract.remaining_total(), 0);
+    assert_eq!(test.contract.beneficiary_status(&test.claim_address[1]), symbol_short!("none"));
+}
+
+#[test]
+fn test_revoke_all_keeps_credits() {
+    let test = ClaimableBalanceTest::setup();
+    let compliance = Address::generate(&test.env);
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &250, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.set_credit_claims(&true);
+    test.contract.claim_amount(&test.claim_address[0], &60);
+
+    test.contract.revoke_all(&compliance);
+    assert_eq!(test.token.balance(&compliance), 190);
+    assert_eq!(test.contract.withdraw(&test.claim_address[0]), 60);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 60);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "irrevocable")]
+fn test_revoke_all_irrevocable_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{irrevocable: true, ..config(&test, vec![&test.env, test.claim_address[0].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+
+    test.contract.revoke_all(&test.deposit_address);
+}
+
+#[test]
+fn test_sweep_on_irrevocable_deposit() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{irrevocable: true, ..config(&test, vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+    test.contract.renounce(&test.claim_address[0]);
+
+    assert_eq!(test.contract.sweep(), 100);
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+}
+
+#[test]
+#[should_panic(expected = "irrevocable")]
+fn test_revoke_all_after_make_irrevocable_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &100, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.make_irrevocable();
+
+    test.contract.revoke_all(&test.deposit_address);
+}
+
+#[test]
+#[should_panic(expected = "distribution complete")]
+fn test_claim_after_revoke_all_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.revoke_all(&test.deposit_address);
+    test.contract.claim(&test.claim_address[0]);
+}
+
+#[test]
+#[should_panic(expected = "upgrade pending")]
+fn test_claim_while_upgrade_pending_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.schedule_upgrade();
+
+    test.contract.claim(&test.claim_address[0]);
+}
+
+#[test]
+#[should_panic(expected = "upgrade pending")]
+fn test_deposit_and_distribute_while_upgrade_pending_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.schedule_upgrade();
+
+    test.contract.deposit_and_distribute(&test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()]);
+}
+
+#[test]
+#[should_panic(expected = "upgrade pending")]
+fn test_contribute_while_upgrade_pending_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit_pool(
+        &test.deposit_address, &test.token.address, &100, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12346});
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.schedule_upgrade();
+
+    test.contract.contribute(&test.deposit_address, &100);
+}
+
+#[test]
+#[should_panic(expected = "upgrade pending")]
+fn test_deposit_while_upgrade_pending_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.approve_admin(&test.deposit_address);
+    test.contract.schedule_upgrade();
+
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+}
+
+#[test]
+fn test_claim_after_upgrade_cancelled() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.init_admin(&Address::generate(&test.env));
+    test.contract.schedule_upgrade();
+    test.contract.cancel_upgrade();
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+#[test]
+fn test_get_token_matches_balance() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    assert_eq!(test.contract.get_token(), test.token.address);
+    assert_eq!(test.contract.get_token(), test.contract.get_balance().token);
+}
+
+#[test]
+fn test_claimable_batch() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.claim(&test.claim_address[1]);
+
+    assert_eq!(
+        test.contract.claimable_batch(&vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()]),
+        vec![&test.env, true, false, false]
+    );
+}
+
+#[contract]
+pub struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn set_members(env: Env, members: soroban_sdk::Vec<Address>) {
+        env.storage().instance().set(&symbol_short!("members"), &members);
+    }
+
+    pub fn members(env: Env) -> soroban_sdk::Vec<Address> {
+        env.storage().instance().get(&symbol_short!("members")).unwrap()
+    }
+}
+
+#[test]
+fn test_deposit_from_registry() {
+    let test = ClaimableBalanceTest::setup();
+    let registry = MockRegistryClient::new(&test.env, &test.env.register_contract(None, MockRegistry));
+    let members = vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()];
+    registry.set_members(&members);
+
+    test.contract.deposit_from_registry(
+        &test.deposit_address, &test.token.address, &100, &registry.address, &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    registry.set_members(&vec![&test.env, test.claim_address[0].clone()]);
+
+    assert_eq!(test.contract.get_balance().beneficiaries, members);
+    assert_eq!(test.token.balance(&test.contract.address), 300);
+    test.contract.claim(&test.claim_address[2]);
+    assert_eq!(test.token.balance(&test.claim_address[2]), 100);
+}
+
+#[test]
+fn test_co_signed_claim() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.set_co_sign(&true);
+
+    test.contract.claim(&test.claim_address[0]);
+    let signers: std::vec::Vec<Address> = test.env.auths().into_iter().map(|(address, _)| address).collect();
+    assert_eq!(signers, std::vec![test.claim_address[0].clone(), test.deposit_address.clone()]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+fn co_signed_claim_with_single_auth(signer: usize) {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.set_co_sign(&true);
+
+    let signer = [test.claim_address[0].clone(), test.deposit_address.clone()][signer].clone();
+    test.contract
+        .mock_auths(&[MockAuth {
+            address: &signer,
+            invoke: &MockAuthInvoke {
+                contract: &test.contract.address,
+                fn_name: "claim",
+                args: (test.claim_address[0].clone(),).into_val(&test.env),
+                sub_invokes: &[],
+            },
+        }])
+        .claim(&test.claim_address[0]);
+}
+
+#[test]
+#[should_panic]
+fn test_co_signed_claim_beneficiary_only_fail() {
+    co_signed_claim_with_single_auth(0);
+}
+
+#[test]
+#[should_panic]
+fn test_co_signed_claim_depositor_only_fail() {
+    co_signed_claim_with_single_auth(1);
+}
+
+#[test]
+fn test_remaining_total() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(test.contract.remaining_total(), 200);
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.contract.remaining_total(), 100);
+
+    test.contract.claim(&test.claim_address[1]);
+    assert_eq!(test.contract.remaining_total(), 0);
+}
+
+#[test]
+fn test_claim_at_before_boundary() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12346});
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+#[test]
+fn test_claim_at_after_boundary() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12346});
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12346});
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+#[test]
+fn test_migrate_v1_balance() {
+    let test = ClaimableBalanceTest::setup();
+    let admin = Address::generate(&test.env);
+    test.env.as_contract(&test.contract.address, || {
+        test.env.storage().instance().set(&DataKey::Init, &true);
+        test.env.storage().instance().set(
+            &DataKey::Balance,
+            &ClaimableBalanceV1 {
+                token: test.token.address.clone(),
+                amount_per_beneficiary: 100,
+                total_amount: 100,
+                beneficiaries: vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()],
+                claimed_beneficiaries: vec![&test.env, test.claim_address[1].clone()],
+                time_bound: TimeBound{kind: TimeBoundKind::Before, timestamp: 12346},
+            },
+        );
+    });
+    test.token.transfer(&test.deposit_address, &test.contract.address, &100);
+    test.contract.init_admin(&admin);
+
+    test.contract.migrate();
+
+    let balance = test.contract.get_balance();
+    assert_eq!(balance.total_amount, 100);
+    assert_eq!(balance.claimed_beneficiaries, vec![&test.env, test.claim_address[1].clone()]);
+    assert!(balance.released);
+    assert_eq!(test.contract.get_token(), test.token.address);
+    assert_eq!(test.contract.get_depositor(), admin);
+
+    test.contract.claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+#[test]
+fn test_protocol() {
+    let test = ClaimableBalanceTest::setup();
+    assert_eq!(test.contract.protocol(), test.env.ledger().protocol_version());
+
+    test.env.ledger().with_mut(|li| {li.protocol_version = 21});
+    assert_eq!(test.contract.protocol(), 21);
+}
+
+#[test]
+fn test_contract_address() {
+    let test = ClaimableBalanceTest::setup();
+    assert_eq!(test.contract.contract_address(), test.contract.address);
+}
+
+#[test]
+fn test_operator_claim_before_expiry() {
+    let test = ClaimableBalanceTest::setup();
+    let operator = Address::generate(&test.env);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12345});
+    test.contract.authorize_operator(&test.claim_address[0], &operator, &12400);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12400});
+    test.contract.claim_for(&operator, &test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    assert_eq!(test.token.balance(&operator), 0);
+}
+
+#[test]
+#[should_panic(expected = "operator authorization expired")]
+fn test_operator_claim_after_expiry_fail() {
+    let test = ClaimableBalanceTest::setup();
+    let operator = Address::generate(&test.env);
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12345});
+    test.contract.authorize_operator(&test.claim_address[0], &operator, &12400);
+
+    test.env.ledger().with_mut(|li| {li.timestamp = 12401});
+    test.contract.claim_for(&operator, &test.claim_address[0]);
+}
+
+#[test]
+#[should_panic(expected = "beneficiary not in list")]
+fn test_authorize_operator_non_beneficiary_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12345});
+    test.contract.authorize_operator(&test.claim_address[1], &Address::generate(&test.env), &12400);
+}
+
+#[test]
+fn test_two_step_claim() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12345});
+    test.contract.set_claim_delay(&Some(3600));
+
+    assert_eq!(test.contract.initiate_claim(&test.claim_address[0]), 12345 + 3600);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12345 + 3600});
+    test.contract.finalize_claim(&test.claim_address[0]);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+}
+
+#[test]
+#[should_panic(expected = "claim delay not elapsed")]
+fn test_two_step_claim_premature_finalize_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12345});
+    test.contract.set_claim_delay(&Some(3600));
+
+    test.contract.initiate_claim(&test.claim_address[0]);
+    test.env.ledger().with_mut(|li| {li.timestamp = 12345 + 3599});
+    test.contract.finalize_claim(&test.claim_address[0]);
+}
+
+#[test]
+#[should_panic(expected = "claim not initiated")]
+fn test_two_step_claim_without_initiate_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12345});
+    test.contract.set_claim_delay(&Some(3600));
+
+    test.contract.claim(&test.claim_address[0]);
+}
+
+#[test]
+#[should_panic(expected = "claim not yet open")]
+fn test_initiate_claim_before_open_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12400});
+    test.contract.set_claim_delay(&Some(3600));
+
+    test.contract.initiate_claim(&test.claim_address[0]);
+}
+
+#[test]
+fn test_claim_after_delay_cleared_removes_initiated_claim() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::After, timestamp: 12345});
+    test.contract.set_claim_delay(&Some(3600));
+    test.contract.initiate_claim(&test.claim_address[0]);
+    test.contract.initiate_claim(&test.claim_address[1]);
+    test.contract.set_claim_delay(&None);
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.distribute_range(&1, &1);
+
+    test.env.as_contract(&test.contract.address, || {
+        assert!(!test.env.storage().instance().has(&DataKey::PendingClaim(test.claim_address[0].clone())));
+        assert!(!test.env.storage().instance().has(&DataKey::PendingClaim(test.claim_address[1].clone())));
+    });
+}
+
+#[test]
+fn test_claim_split() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    let wallets = vec![&test.env, Address::generate(&test.env), Address::generate(&test.env)];
+
+    test.contract.claim_split(&test.claim_address[0], &wallets, &vec![&test.env, 6000, 4000]);
+
+    assert_eq!(test.token.balance(&wallets.get(0).unwrap()), 60);
+    assert_eq!(test.token.balance(&wallets.get(1).unwrap()), 40);
+    assert_eq!(test.token.balance(&test.claim_address[0]), 0);
+    assert_eq!(test.token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_claim_split_large_amount() {
+    let test = ClaimableBalanceTest::setup();
+    let (token, token_admin_client) = create_token_contract(&test.env, &Address::generate(&test.env));
+    token_admin_client.mint(&test.deposit_address, &i128::MAX);
+    test.contract.deposit(
+        &test.deposit_address, &token.address, &i128::MAX, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    let wallets = vec![&test.env, Address::generate(&test.env), Address::generate(&test.env)];
+
+    test.contract.claim_split(&test.claim_address[0], &wallets, &vec![&test.env, 5000, 5000]);
+    assert_eq!(token.balance(&wallets.get(0).unwrap()), i128::MAX / 2);
+    assert_eq!(token.balance(&wallets.get(1).unwrap()), i128::MAX / 2 + 1);
+    assert_eq!(token.balance(&test.contract.address), 0);
+}
+
+#[test]
+fn test_claim_split_credited() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.set_credit_claims(&true);
+    let wallets = vec![&test.env, Address::generate(&test.env), Address::generate(&test.env)];
+
+    test.contract.claim_split(&test.claim_address[0], &wallets, &vec![&test.env, 6000, 4000]);
+    assert_eq!(test.token.balance(&wallets.get(0).unwrap()), 0);
+    assert_eq!(test.contract.credit(&wallets.get(0).unwrap()), 60);
+    assert_eq!(test.contract.credit(&wallets.get(1).unwrap()), 40);
+    assert_eq!(test.contract.withdraw(&wallets.get(1).unwrap()), 40);
+    assert_eq!(test.token.balance(&wallets.get(1).unwrap()), 40);
+}
+
+#[test]
+fn test_claim_split_defers_failed_part() {
+    let test = ClaimableBalanceTest::setup();
+    let token = FreezableTokenClient::new(&test.env, &test.env.register_contract(None, FreezableToken));
+    token.mint(&test.deposit_address, &100);
+    test.contract.deposit_config(
+        &test.deposit_address, &DepositConfig{token: token.address.clone(), defer_failed_transfers: true, ..config(&test, vec![&test.env, test.claim_address[0].clone()], TimeBound{kind: TimeBoundKind::Before, timestamp: 12346})});
+    let wallets = vec![&test.env, Address::generate(&test.env), Address::generate(&test.env)];
+    token.freeze_account(&wallets.get(1).unwrap());
+
+    test.contract.claim_split(&test.claim_address[0], &wallets, &vec![&test.env, 6000, 4000]);
+    assert_eq!(token.balance(&wallets.get(0).unwrap()), 60);
+    assert_eq!(token.balance(&wallets.get(1).unwrap()), 0);
+    assert_eq!(test.contract.credit(&wallets.get(1).unwrap()), 40);
+}
+
+#[test]
+#[should_panic(expected = "length mismatch")]
+fn test_claim_split_length_mismatch_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.claim_split(&test.claim_address[0], &vec![&test.env, Address::generate(&test.env)], &vec![&test.env, 6000, 4000]);
+}
+
+#[test]
+fn test_description() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    assert_eq!(test.contract.get_description(), String::from_str(&test.env, ""));
+
+    let description = String::from_str(&test.env, "Q1 2025 Grants");
+    test.contract.set_description(&description);
+    assert_eq!(test.contract.get_description(), description);
+}
+
+#[test]
+#[should_panic(expected = "description too long")]
+fn test_description_too_long_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+
+    test.contract.set_description(&String::from_str(&test.env, &"x".repeat(65)));
+}
+
+#[test]
+#[should_panic(expected = "underfunded distribution")]
+fn test_claim_underfunded_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.env.as_contract(&test.contract.address, || {
+        let mut balance: ClaimableBalance = test.env.storage().instance().get(&DataKey::Balance).unwrap();
+        balance.beneficiaries.push_back(test.claim_address[2].clone());
+        test.env.storage().instance().set(&DataKey::Balance, &balance);
+    });
+
+    test.contract.claim(&test.claim_address[0]);
+    test.contract.claim(&test.claim_address[1]);
+    test.contract.claim(&test.claim_address[2]);
+}
+
+#[test]
+#[should_panic(expected = "underfunded distribution")]
+fn test_claim_underfunded_with_credits_fail() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.set_credit_claims(&true);
+    test.contract.claim(&test.claim_address[0]);
+    test.env.as_contract(&test.contract.address, || {
+        test.token.transfer(&test.contract.address, &test.deposit_address, &50);
+    });
+
+    test.contract.claim(&test.claim_address[1]);
+}
+
+#[test]
+fn test_summary() {
+    let test = ClaimableBalanceTest::setup();
+    test.contract.deposit(
+        &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+    test.contract.claim(&test.claim_address[1]);
+
+    let (token, amount_per_beneficiary, total_count, claimed_count, time_bound) = test.contract.summary();
+    assert_eq!(token, test.token.address);
+    assert_eq!(amount_per_beneficiary, 100);
+    assert_eq!(total_count, 3);
+    assert_eq!(claimed_count, 1);
+    assert!(matches!(time_bound.kind, TimeBoundKind::Before));
+    assert_eq!(time_bound.timestamp, 12346);
+}
+
+/// Claim receipts minted through a cross-contract call to an attestation contract.
+mod attestation {
+    use super::*;
+
+    #[contract]
+    pub struct MockAttestation;
+
+    #[contractimpl]
+    impl MockAttestation {
+        pub fn mint(env: Env, to: Address) {
+            let mut minted: soroban_sdk::Vec<Address> = Self::minted(env.clone());
+            minted.push_back(to);
+            env.storage().instance().set(&symbol_short!("minted"), &minted);
+        }
+
+        pub fn minted(env: Env) -> soroban_sdk::Vec<Address> {
+            env.storage().instance().get(&symbol_short!("minted")).unwrap_or_else(|| vec![&env])
+        }
+    }
+
+    #[test]
+    fn test_claim_mints_attestation() {
+        let test = ClaimableBalanceTest::setup();
+        let attestation = MockAttestationClient::new(&test.env, &test.env.register_contract(None, MockAttestation));
+        test.contract.deposit(
+            &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+        test.contract.set_attestation_contract(&Some(attestation.address.clone()));
+
+        test.contract.claim(&test.claim_address[1]);
+        assert_eq!(attestation.minted(), vec![&test.env, test.claim_address[1].clone()]);
+        assert_eq!(test.token.balance(&test.claim_address[1]), 100);
+    }
+
+    #[test]
+    fn test_claim_survives_failing_attestation() {
+        let test = ClaimableBalanceTest::setup();
+        test.contract.deposit(
+            &test.deposit_address, &test.token.address, &100, &vec![&test.env, test.claim_address[0].clone()], &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+        test.contract.set_attestation_contract(&Some(test.token.address.clone()));
+
+        test.contract.claim(&test.claim_address[0]);
+        assert_eq!(test.token.balance(&test.claim_address[0]), 100);
+    }
+}
+
+/// Executable spec of the event schema: one event per state transition, in order.
+mod events {
+    use super::*;
+    use soroban_sdk::Val;
+
+    #[test]
+    fn test_lifecycle_events() {
+        let test = ClaimableBalanceTest::setup();
+        let beneficiaries = vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone(), test.claim_address[2].clone()];
+        test.contract.deposit(
+            &test.deposit_address, &test.token.address, &100, &beneficiaries, &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+        test.contract.claim(&test.claim_address[0]);
+        test.contract.renounce(&test.claim_address[1]);
+        test.contract.claim(&test.claim_address[2]);
+        test.contract.sweep();
+
+        let mut emitted = vec![&test.env];
+        for event in test.env.events().all().iter() {
+            if event.0 == test.contract.address {
+                emitted.push_back(event);
+            }
+        }
+
+        let expected: soroban_sdk::Vec<(Address, soroban_sdk::Vec<Val>, Val)> = vec![
+            &test.env,
+            (
+                test.contract.address.clone(),
+                (symbol_short!("deposit"), test.deposit_address.clone()).into_val(&test.env),
+                (test.token.address.clone(), 100_i128, beneficiaries.clone()).into_val(&test.env),
+            ),
+            (
+                test.contract.address.clone(),
+                (symbol_short!("claim"), test.claim_address[0].clone()).into_val(&test.env),
+                (100_i128, 200_i128).into_val(&test.env),
+            ),
+            (
+                test.contract.address.clone(),
+                (symbol_short!("renounce"), test.claim_address[1].clone()).into_val(&test.env),
+                100_i128.into_val(&test.env),
+            ),
+            (
+                test.contract.address.clone(),
+                (symbol_short!("claim"), test.claim_address[2].clone()).into_val(&test.env),
+                (100_i128, 0_i128).into_val(&test.env),
+            ),
+            (
+                test.contract.address.clone(),
+                (symbol_short!("sweep"), test.deposit_address.clone()).into_val(&test.env),
+                100_i128.into_val(&test.env),
+            ),
+        ];
+        assert_eq!(emitted, expected);
+    }
+
+    #[test]
+    fn test_pool_and_config_events() {
+        let test = ClaimableBalanceTest::setup();
+        let beneficiaries = vec![&test.env, test.claim_address[0].clone(), test.claim_address[1].clone()];
+        test.contract.deposit_pool(
+            &test.deposit_address, &test.token.address, &150, &100, &beneficiaries, &TimeBound{kind: TimeBoundKind::Before, timestamp: 12346});
+        let admin = Address::generate(&test.env);
+        test.contract.init_admin(&admin);
+        test.contract.set_max_amount(&Some(500));
+        test.contract.blacklist_add(&test.claim_address[1]);
+        test.contract.blacklist_remove(&test.claim_address[1]);
+
+        let mut emitted = vec![&test.env];
+        for event in test.env.events().all().iter() {
+            if event.0 == test.contract.address {
+                emitted.push_back(event);
+            }
+        }
+
+        let expected: soroban_sdk::Vec<(Address, soroban_sdk::Vec<Val>, Val)> = vec![
+            &test.env,
+            (
+                test.contract.address.clone(),
+                (symbol_short!("pool"), test.deposit_address.clone()).into_val(&test.env),
+                (test.token.address.clone(), 150_i128, 100_i128, beneficiaries.clone()).into_val(&test.env),
+            ),
+            (
+                test.contract.address.clone(),
+                (symbol_short!("admin"),).into_val(&test.env),
+                admin.into_val(&test.env),
+            ),
+            (
+                test.contract.address.clone(),
+                (symbol_short!("config"), symbol_short!("max_amt")).into_val(&test.env),
+                Some(500_i128).into_val(&test.env),
+            ),
+            (
+                test.contract.address.clone(),
+                (symbol_short!("blacklist"), test.claim_address[1].clone()).into_val(&test.env),
+                true.into_val(&test.env),
+            ),
+            (
+                test.contract.address.clone(),
+                (symbol_short!("blacklist"), test.claim_address[1].clone()).into_val(&test.env),
+                false.into_val(&test.env),
+            ),
+        ];
+        assert_eq!(emitted, expected);
+    }
+}